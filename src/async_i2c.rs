@@ -1,11 +1,50 @@
-use crate::common::{Error, Library, Mode, MotorType, DRV2605L_ADDR};
+use crate::audio::{audio_drive_code, audio_input_code, AudioFilter, AudioPeakTime};
+#[cfg(feature = "alloc")]
+use crate::common::DynamicSequence;
+use crate::common::{
+    drive_time_scale, drive_time_us, encode_timed_sequence, hz_to_lra_period, init_writes,
+    lra_period_to_hz, lra_period_to_us, morse_code, open_loop_drive_code, open_loop_drive_mv,
+    overdrive_clamp_raw, overdrive_voltage_code, ramp_value, rated_voltage_code, rated_voltage_mv,
+    us_to_lra_period, voltage_code_to_mv, wait_step, ActuatorPreset, AutoCalTime, BlankingTime,
+    BrakeFactor, CalibrationResult, Config, ConflictPolicy, DeviceVariant, Diagnostics, DriveState,
+    Effect, Error, FaultMask, IdissTime, InputMode, LevelMeter, Library, LoopGain, LoopMode, Mode,
+    MotorType, RegisterDump, RegisterWrite, RtpEnvelope, RtpFormat, SampleTime, Status,
+    StressReport, TriggerEdge, WaveformSequence, WaveformStep, ZcDetTime, DRV2605L_ADDR,
+    OVERDRIVE_FULL_SCALE_MV,
+};
 use crate::registers;
-use embassy_time::{Duration, Timer};
+use core::convert::Infallible;
+use core::future::Future;
+use core::ops::{Deref, DerefMut};
+use core::task::Poll;
+use embassy_time::{Duration, Instant, Timer};
 use embedded_hal_async::i2c::I2c;
 
+// Upper bound for a full 8-slot sequence, including maximum-length waits.
+const PLAYBACK_TIMEOUT: Duration = Duration::from_secs(12);
+const PLAYBACK_POLL: Duration = Duration::from_millis(5);
+// Covers the longest AUTO_CAL_TIME setting with margin.
+const CALIBRATION_TIMEOUT: Duration = Duration::from_secs(2);
+const CALIBRATION_POLL: Duration = Duration::from_millis(10);
+const DIAGNOSTICS_TIMEOUT: Duration = Duration::from_millis(1000);
+const DIAGNOSTICS_POLL_INTERVAL: Duration = Duration::from_millis(10);
+const RESET_TIMEOUT: Duration = Duration::from_millis(20);
+// Consecutive resonance-period reads that must agree, within one register step,
+// before `measure_startup_time` treats the motor as locked.
+const STARTUP_SETTLE_READS: u8 = 3;
+const STARTUP_PERIOD_TOLERANCE: u8 = 1;
+
 pub struct Drv2605l<I2C> {
     i2c: I2C,
+    address: u8,
     motor_type: MotorType,
+    config: Config,
+    config_locked: bool,
+    calibration: Option<CalibrationResult>,
+    stop_pending: bool,
+    standby_pending: bool,
+    retries: u8,
+    retry_backoff: Duration,
 }
 
 impl<I2C, E> Drv2605l<I2C>
@@ -13,28 +52,212 @@ where
     I2C: I2c<Error = E>,
 {
     pub fn new(i2c: I2C) -> Self {
+        Self::new_with_config(i2c, Config::default())
+    }
+
+    /// For boards where the part answers somewhere other than `DRV2605L_ADDR`.
+    pub fn new_with_address(i2c: I2C, address: u8) -> Self {
+        let mut driver = Self::new(i2c);
+        driver.address = address;
+        driver
+    }
+
+    pub fn new_with_config(i2c: I2C, config: Config) -> Self {
+        let motor_type = config.motor_type.unwrap_or(MotorType::LRA);
         Self {
             i2c,
-            motor_type: MotorType::LRA,
+            address: DRV2605L_ADDR,
+            motor_type,
+            config: config.motor_type(motor_type),
+            config_locked: false,
+            calibration: None,
+            stop_pending: false,
+            standby_pending: false,
+            retries: 0,
+            retry_backoff: Duration::from_ticks(0),
         }
     }
 
+    /// Retries every failed register transfer up to `retries` more times,
+    /// waiting `backoff` between attempts, before returning the error. The
+    /// default of zero retries fails on the first error.
+    pub fn set_retry_policy(&mut self, retries: u8, backoff: Duration) {
+        self.retries = retries;
+        self.retry_backoff = backoff;
+    }
+
+    /// Makes configuration setters return `Error::ConfigLocked` until
+    /// `unlock_config`. The PWM, analog and audio-to-vibe entry points count as
+    /// setters because they rewrite Control3; playback methods are unaffected.
+    pub fn lock_config(&mut self) {
+        self.config_locked = true;
+    }
+
+    pub fn unlock_config(&mut self) {
+        self.config_locked = false;
+    }
+
+    pub fn is_config_locked(&self) -> bool {
+        self.config_locked
+    }
+
+    /// Gives back the I2C bus, e.g. to hand it to another device at shutdown.
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+
+    /// Direct access to the bus. Transactions made through it bypass the driver.
+    pub fn inner_mut(&mut self) -> &mut I2C {
+        &mut self.i2c
+    }
+
     pub async fn init(&mut self) -> Result<(), Error<E>> {
         self.reset().await?;
         Timer::after(Duration::from_millis(2)).await;
+        self.configure().await
+    }
 
-        self.exit_standby().await?;
+    /// Full power-up sequence: reset, wait for DEV_RESET to self-clear, then
+    /// program the same state as `init`, leaving the device out of standby in
+    /// [`Mode::InternalTrigger`] with the stored motor type applied.
+    pub async fn reset_and_init(&mut self) -> Result<(), Error<E>> {
+        self.reset().await?;
+        let deadline = Instant::now() + RESET_TIMEOUT;
+        loop {
+            Timer::after(Duration::from_millis(1)).await;
+            if self.read_register(registers::MODE).await? & 0x80 == 0 {
+                break;
+            }
+            if Instant::now() >= deadline {
+                return Err(Error::Timeout);
+            }
+        }
+        self.configure().await
+    }
 
-        if self.motor_type == MotorType::LRA {
-            self.write_register(registers::FEEDBACK_CONTROL, 0x80)
-                .await?;
-            self.set_library(Library::LRA).await?;
-        } else {
-            self.write_register(registers::FEEDBACK_CONTROL, 0x00)
+    async fn configure(&mut self) -> Result<(), Error<E>> {
+        if let Some(motor_type) = self.config.motor_type {
+            self.motor_type = motor_type;
+        }
+        for write in init_writes(&self.config) {
+            self.apply_write(write).await?;
+        }
+        self.apply_config_options().await?;
+
+        if self.config.auto_supply_compensation {
+            self.apply_supply_compensation().await?;
+        }
+
+        Ok(())
+    }
+
+    /// Resets the chip and programs the whole `Config` in one go: exit standby,
+    /// feedback and library, then voltages. Unlike `init` this honors `lock_config`.
+    pub async fn apply_config(&mut self) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        self.init().await
+    }
+
+    /// Re-runs `init` if the chip no longer holds the settings `init`
+    /// programmed, e.g. after a brown-out reset, then re-applies the last
+    /// calibration from `auto_calibrate` or `apply_calibration`. Feedback Control
+    /// and the library are always checked, and so are the rated voltage and
+    /// OD_CLAMP when configured; rated voltage is skipped with
+    /// `auto_supply_compensation`, which may have lowered it. Runtime changes made
+    /// through the setters count as programmed. Returns whether recovery was
+    /// needed.
+    pub async fn detect_and_recover(&mut self) -> Result<bool, Error<E>> {
+        if self.registers_intact().await? {
+            return Ok(false);
+        }
+
+        self.init().await?;
+        if let Some(cal) = self.calibration {
+            self.write_calibration(&cal).await?;
+        }
+        Ok(true)
+    }
+
+    async fn registers_intact(&mut self) -> Result<bool, Error<E>> {
+        let feedback = self.read_register(registers::FEEDBACK_CONTROL).await?;
+        let library = self.read_register(registers::LIBRARY_SELECTION).await?;
+        let intact = init_writes(&self.config)
+            .iter()
+            .all(|write| match write.reg {
+                registers::FEEDBACK_CONTROL => feedback & write.mask == write.value & write.mask,
+                registers::LIBRARY_SELECTION => library & write.mask == write.value & write.mask,
+                _ => true,
+            });
+        if !intact {
+            return Ok(false);
+        }
+
+        let config = self.config;
+        if let (Some(mv), false) = (config.rated_voltage_mv, config.auto_supply_compensation) {
+            let expected = self.encode_rated_voltage(mv).await?;
+            if self.read_register(registers::RATED_VOLTAGE).await? != expected {
+                return Ok(false);
+            }
+        }
+        let od_clamp = match (
+            config.overdrive_voltage_mv,
+            config.open_loop_drive_voltage_mv,
+        ) {
+            (Some(mv), _) => Some(overdrive_voltage_code(mv).ok_or(Error::InvalidParameter)?),
+            (None, Some(mv)) => Some(self.encode_open_loop_drive_voltage(mv).await?),
+            (None, None) => None,
+        };
+        if let Some(expected) = od_clamp {
+            if self
+                .read_register(registers::OVERDRIVE_CLAMP_VOLTAGE)
+                .await?
+                != expected
+            {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Writes the optional `Config` fields directly, so recovery via `init`
+    /// still works while the configuration is locked.
+    async fn apply_config_options(&mut self) -> Result<(), Error<E>> {
+        let config = self.config;
+        if let Some(loop_mode) = config.loop_mode {
+            self.write_loop_mode(loop_mode).await?;
+        }
+        if let Some(mv) = config.rated_voltage_mv {
+            self.write_rated_voltage(mv).await?;
+        }
+        if let Some(mv) = config.overdrive_voltage_mv {
+            let value = overdrive_voltage_code(mv).ok_or(Error::InvalidParameter)?;
+            self.write_register(registers::OVERDRIVE_CLAMP_VOLTAGE, value)
                 .await?;
-            self.set_library(Library::LibraryB).await?;
         }
+        if let Some(mv) = config.open_loop_drive_voltage_mv {
+            self.write_open_loop_drive_voltage(mv).await?;
+        }
+        Ok(())
+    }
+
+    async fn apply_supply_compensation(&mut self) -> Result<(), Error<E>> {
+        let vbat = self.read_register(registers::VBAT_VOLTAGE_MONITOR).await?;
+        self.modify_register(registers::CONTROL3, 0x10, 0x00)
+            .await?;
 
+        // VBAT is on the 5.6 V full-scale code, RATED_VOLTAGE on the motor's own
+        // scale. A supply above the rated range needs no clamp.
+        let (sample_time, lra_hz) = self.rated_voltage_scale().await?;
+        let supply_mv = voltage_code_to_mv(vbat);
+        let Some(limit) = rated_voltage_code(self.motor_type, supply_mv, sample_time, lra_hz)
+        else {
+            return Ok(());
+        };
+
+        let rated = self.read_register(registers::RATED_VOLTAGE).await?;
+        if rated > limit {
+            self.write_register(registers::RATED_VOLTAGE, limit).await?;
+        }
         Ok(())
     }
 
@@ -50,34 +273,163 @@ where
         self.write_register(registers::MODE, 0x40).await
     }
 
+    /// Leaves standby and returns a guard that puts the device back into it.
+    /// The device needs about 1 ms after waking before it plays reliably.
+    /// End the guard with `sleep`; see [`StandbyGuard`] for what happens on drop.
+    pub async fn wake(&mut self) -> Result<StandbyGuard<'_, I2C>, Error<E>> {
+        self.exit_standby().await?;
+        Ok(StandbyGuard {
+            driver: self,
+            armed: true,
+        })
+    }
+
+    /// Switches the operating mode, keeping the standby bit. Leaving RTP or
+    /// audio-to-vibe mode stops playback (clears GO) first, since changing
+    /// mode with GO set can glitch the output.
     pub async fn set_mode(&mut self, mode: Mode) -> Result<(), Error<E>> {
         let current = self.read_register(registers::MODE).await?;
+        let current_mode = current & 0x07;
+        let streaming =
+            current_mode == Mode::RealTimePlayback as u8 || current_mode == Mode::AudioToVibe as u8;
+        if streaming && current_mode != mode as u8 {
+            self.stop().await?;
+        }
+
         let new_value = (current & 0xF8) | (mode as u8);
         self.write_register(registers::MODE, new_value).await
     }
 
+    /// Current operating mode, ignoring the standby bit.
+    pub async fn get_mode(&mut self) -> Result<Mode, Error<E>> {
+        let current = self.read_register(registers::MODE).await?;
+        Mode::try_from(current & 0x07).map_err(|_| Error::InvalidParameter)
+    }
+
     pub async fn set_library(&mut self, library: Library) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
         self.write_register(registers::LIBRARY_SELECTION, library as u8)
-            .await
+            .await?;
+        self.config.library = Some(library);
+        Ok(())
     }
 
     pub async fn set_motor_type(&mut self, motor_type: MotorType) -> Result<(), Error<E>> {
-        self.motor_type = motor_type;
+        self.ensure_unlocked()?;
+        self.track_motor_type(motor_type);
 
         match motor_type {
             MotorType::LRA => {
-                self.write_register(registers::FEEDBACK_CONTROL, 0x80)
+                self.modify_register(registers::FEEDBACK_CONTROL, 0x80, 0x80)
                     .await?;
                 self.set_library(Library::LRA).await
             }
             MotorType::ERM => {
-                self.write_register(registers::FEEDBACK_CONTROL, 0x00)
+                self.modify_register(registers::FEEDBACK_CONTROL, 0x80, 0x00)
                     .await?;
                 self.set_library(Library::LibraryB).await
             }
         }
     }
 
+    /// Motor type the chip is actually configured for (N_ERM_LRA). The cached
+    /// type used by other methods is updated to match if it had drifted, but the
+    /// stored `Config` is not, so `detect_and_recover` still restores the
+    /// configured type after a reset. A mismatch is logged with defmt.
+    pub async fn get_motor_type(&mut self) -> Result<MotorType, Error<E>> {
+        let feedback = self.read_register(registers::FEEDBACK_CONTROL).await?;
+        let motor_type = if feedback & 0x80 != 0 {
+            MotorType::LRA
+        } else {
+            MotorType::ERM
+        };
+        #[cfg(feature = "defmt")]
+        if self.config.motor_type != Some(motor_type) {
+            defmt::warn!(
+                "drv2605l N_ERM_LRA reads {} but {} is configured",
+                motor_type,
+                self.config.motor_type
+            );
+        }
+        self.motor_type = motor_type;
+        Ok(motor_type)
+    }
+
+    pub async fn set_brake_factor(&mut self, factor: BrakeFactor) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        self.modify_register(registers::FEEDBACK_CONTROL, 0x70, (factor as u8) << 4)
+            .await?;
+        self.config.brake_factor = Some(factor);
+        Ok(())
+    }
+
+    pub async fn set_loop_gain(&mut self, gain: LoopGain) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        self.modify_register(registers::FEEDBACK_CONTROL, 0x0C, (gain as u8) << 2)
+            .await?;
+        self.config.loop_gain = Some(gain);
+        Ok(())
+    }
+
+    pub async fn set_sample_time(&mut self, time: SampleTime) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        self.modify_register(registers::CONTROL2, 0x30, (time as u8) << 4)
+            .await
+    }
+
+    pub async fn set_blanking_time(&mut self, time: BlankingTime) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        self.modify_register(registers::CONTROL2, 0x0C, (time as u8) << 2)
+            .await
+    }
+
+    pub async fn set_idiss_time(&mut self, time: IdissTime) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        self.modify_register(registers::CONTROL2, 0x03, time as u8)
+            .await
+    }
+
+    /// Longer calibration helps actuators that have not settled when the
+    /// default run ends.
+    pub async fn set_auto_cal_time(&mut self, time: AutoCalTime) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        self.modify_register(registers::CONTROL4, 0x30, (time as u8) << 4)
+            .await
+    }
+
+    pub async fn set_zero_cross_det_time(&mut self, time: ZcDetTime) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        self.modify_register(registers::CONTROL4, 0xC0, (time as u8) << 6)
+            .await
+    }
+
+    /// Sets open or closed loop for the current motor type; ERM and LRA use
+    /// separate bits in Control3.
+    pub async fn set_loop_mode(&mut self, loop_mode: LoopMode) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        self.write_loop_mode(loop_mode).await?;
+        self.config.loop_mode = Some(loop_mode);
+        Ok(())
+    }
+
+    async fn write_loop_mode(&mut self, loop_mode: LoopMode) -> Result<(), Error<E>> {
+        let bit = self.open_loop_bit();
+        let value = match loop_mode {
+            LoopMode::OpenLoop => bit,
+            LoopMode::ClosedLoop => 0x00,
+        };
+        self.modify_register(registers::CONTROL3, bit, value).await
+    }
+
+    pub async fn get_loop_mode(&mut self) -> Result<LoopMode, Error<E>> {
+        let control3 = self.read_register(registers::CONTROL3).await?;
+        if control3 & self.open_loop_bit() != 0 {
+            Ok(LoopMode::OpenLoop)
+        } else {
+            Ok(LoopMode::ClosedLoop)
+        }
+    }
+
     pub async fn go(&mut self) -> Result<(), Error<E>> {
         self.write_register(registers::GO, 0x01).await
     }
@@ -86,97 +438,1370 @@ where
         self.write_register(registers::GO, 0x00).await
     }
 
+    /// Sets GO and returns a guard for stopping playback. `PlayGuard::stop`
+    /// stops it on the spot; dropping the guard is best-effort and only stops
+    /// playback once the driver is next used, so a cancelled task that never
+    /// touches the driver again leaves the motor running until the sequence
+    /// ends. See [`PlayGuard`].
+    pub async fn trigger_guarded(&mut self) -> Result<PlayGuard<'_, I2C>, Error<E>> {
+        self.go().await?;
+        Ok(PlayGuard {
+            driver: self,
+            armed: true,
+        })
+    }
+
     pub async fn is_playing(&mut self) -> Result<bool, Error<E>> {
         let go_reg = self.read_register(registers::GO).await?;
         Ok(go_reg & 0x01 != 0)
     }
 
-    pub async fn set_waveform(&mut self, slot: u8, effect: u8) -> Result<(), Error<E>> {
+    /// One GO check for callers driving their own state machine: `Ready`
+    /// once playback or calibration has finished, `Pending` while it runs.
+    pub async fn poll_completion(&mut self) -> Result<Poll<()>, Error<E>> {
+        if self.is_playing().await? {
+            Ok(Poll::Pending)
+        } else {
+            Ok(Poll::Ready(()))
+        }
+    }
+
+    /// Polls the GO bit every `poll_interval` until playback or calibration
+    /// finishes, failing with `Error::Timeout` once `timeout` has passed.
+    pub async fn wait_until_done(
+        &mut self,
+        poll_interval: Duration,
+        timeout: Duration,
+    ) -> Result<(), Error<E>> {
+        let deadline = Instant::now() + timeout;
+        while self.is_playing().await? {
+            if Instant::now() >= deadline {
+                return Err(Error::Timeout);
+            }
+            Timer::after(poll_interval).await;
+        }
+        Ok(())
+    }
+
+    pub async fn set_waveform(
+        &mut self,
+        slot: u8,
+        step: impl Into<WaveformStep>,
+    ) -> Result<(), Error<E>> {
         if slot > 7 {
             return Err(Error::InvalidParameter);
         }
 
+        let byte = step.into().to_byte().ok_or(Error::InvalidParameter)?;
         let reg = registers::WAVEFORM_SEQUENCER_1 + slot;
-        self.write_register(reg, effect).await
+        self.write_registers(reg, &[byte]).await
+    }
+
+    pub async fn set_wait(&mut self, slot: u8, wait: Duration) -> Result<(), Error<E>> {
+        let ms = u32::try_from(wait.as_millis()).unwrap_or(u32::MAX);
+        let step = wait_step(ms).ok_or(Error::InvalidParameter)?;
+        self.set_waveform(slot, step).await
     }
 
     pub async fn clear_waveform_sequence(&mut self) -> Result<(), Error<E>> {
-        for i in 0..8 {
-            self.set_waveform(i, 0).await?;
+        self.write_registers(registers::WAVEFORM_SEQUENCER_1, &[0; 8])
+            .await
+    }
+
+    /// Typed `set_waveform` that only accepts ROM library effects.
+    pub async fn set_effect(&mut self, slot: u8, effect: Effect) -> Result<(), Error<E>> {
+        self.set_waveform(slot, effect).await
+    }
+
+    /// Typed `play_waveform` that only accepts ROM library effects.
+    pub async fn play_effect(&mut self, effect: Effect) -> Result<(), Error<E>> {
+        self.play_waveform(effect.as_u8()).await
+    }
+
+    /// Loads `effects` into slots 0.. and terminates the sequence if slots remain,
+    /// without setting GO, so it can be armed for an external trigger.
+    pub async fn set_waveform_sequence(&mut self, effects: &[u8]) -> Result<(), Error<E>> {
+        self.load_sequence(effects).await
+    }
+
+    pub async fn play_waveform_sequence(&mut self, effects: &[u8]) -> Result<(), Error<E>> {
+        if effects.len() > 8 {
+            return Err(Error::InvalidParameter);
         }
-        Ok(())
+
+        self.prepare_playback(Mode::InternalTrigger).await?;
+        self.set_waveform_sequence(effects).await?;
+        self.go().await
+    }
+
+    /// Hands the sequence already loaded in the sequencer (see
+    /// `set_waveform_sequence`) to the IN/TRIG pin without setting GO.
+    pub async fn arm_external_trigger(&mut self, edge: TriggerEdge) -> Result<(), Error<E>> {
+        let mode = match edge {
+            TriggerEdge::Edge => Mode::ExternalTriggerEdge,
+            TriggerEdge::Level => Mode::ExternalTriggerLevel,
+        };
+        self.prepare_playback(mode).await
     }
 
+    /// Effect 0 is the sequence terminator and is rejected without touching GO.
     pub async fn play_waveform(&mut self, effect: u8) -> Result<(), Error<E>> {
-        self.set_mode(Mode::InternalTrigger).await?;
+        if effect == 0 {
+            return Err(Error::InvalidParameter);
+        }
+
+        self.prepare_playback(Mode::InternalTrigger).await?;
+        self.write_registers(
+            registers::WAVEFORM_SEQUENCER_1,
+            &[effect, 0, 0, 0, 0, 0, 0, 0],
+        )
+        .await?;
+        self.go().await
+    }
+
+    /// Plays `effect`, waits for it to finish plus the selected library's
+    /// longest brake time so the brake phase is not cut short, then enters
+    /// standby. Use `play_waveform_then_standby_with_guard` to pick the margin.
+    pub async fn play_waveform_then_standby(&mut self, effect: u8) -> Result<(), Error<E>> {
+        let guard = Duration::try_from(self.active_library().max_brake_time())
+            .map_err(|_| Error::InvalidParameter)?;
+        self.play_waveform_then_standby_with_guard(effect, guard)
+            .await
+    }
+
+    /// `play_waveform_then_standby` with an explicit `guard` after GO clears.
+    pub async fn play_waveform_then_standby_with_guard(
+        &mut self,
+        effect: u8,
+        guard: Duration,
+    ) -> Result<(), Error<E>> {
+        self.play_waveform(effect).await?;
+        self.wait_until_done(PLAYBACK_POLL, PLAYBACK_TIMEOUT)
+            .await?;
+        Timer::after(guard).await;
+        self.enter_standby().await
+    }
+
+    #[cfg(feature = "alloc")]
+    pub async fn play_dynamic_sequence(
+        &mut self,
+        sequence: &DynamicSequence,
+    ) -> Result<(), Error<E>> {
+        self.prepare_playback(Mode::InternalTrigger).await?;
+
+        for (i, chunk) in sequence.steps().chunks(8).enumerate() {
+            if i > 0 {
+                self.wait_until_done(PLAYBACK_POLL, PLAYBACK_TIMEOUT)
+                    .await?;
+            }
+            self.load_sequence(chunk).await?;
+            self.go().await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn play_sequence(&mut self, sequence: &WaveformSequence) -> Result<(), Error<E>> {
+        if !sequence.is_valid() {
+            return Err(Error::InvalidParameter);
+        }
+
+        self.prepare_playback(Mode::InternalTrigger).await?;
         self.clear_waveform_sequence().await?;
-        self.set_waveform(0, effect).await?;
-        self.set_waveform(1, 0).await?;
+        self.load_sequence(sequence.steps()).await?;
+        self.go().await
+    }
+
+    /// Number of sequencer slots left free after `pattern`, or
+    /// `Error::InvalidParameter` if it does not fit `play_timed_sequence`.
+    pub fn sequence_capacity(&self, pattern: &[(Effect, Duration)]) -> Result<u8, Error<E>> {
+        let (_, len) = Self::encode_pattern(pattern)?;
+        Ok(8 - len as u8)
+    }
+
+    /// Programs effects separated by hardware wait slots so the chip paces the
+    /// whole pattern itself after a single GO. A zero gap adds no wait slot.
+    pub async fn play_timed_sequence(
+        &mut self,
+        pattern: &[(Effect, Duration)],
+    ) -> Result<(), Error<E>> {
+        let (steps, len) = Self::encode_pattern(pattern)?;
+
+        self.prepare_playback(Mode::InternalTrigger).await?;
+        self.load_sequence(&steps[..len]).await?;
+        self.go().await
+    }
+
+    /// Stops the running effect and starts `effect` in its place using three
+    /// transactions. Assumes the device is already in internal trigger mode.
+    /// Effect 0 is the sequence terminator and is rejected.
+    pub async fn replace_effect(&mut self, effect: u8) -> Result<(), Error<E>> {
+        if effect == 0 {
+            return Err(Error::InvalidParameter);
+        }
+
+        self.stop().await?;
+        self.write_registers(registers::WAVEFORM_SEQUENCER_1, &[effect, 0])
+            .await?;
+        self.go().await
+    }
+
+    /// Pre-programs `effect`, then issues only the GO write once `beat` resolves,
+    /// keeping the latency between the beat and playback to a single transaction.
+    pub async fn play_on_beat(
+        &mut self,
+        effect: u8,
+        beat: impl Future<Output = ()>,
+    ) -> Result<(), Error<E>> {
+        if effect == 0 {
+            return Err(Error::InvalidParameter);
+        }
+
+        self.prepare_playback(Mode::InternalTrigger).await?;
+        self.write_registers(registers::WAVEFORM_SEQUENCER_1, &[effect, 0])
+            .await?;
+
+        beat.await;
         self.go().await
     }
 
+    pub async fn set_rtp_data_format(&mut self, format: RtpFormat) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        let bit = match format {
+            RtpFormat::Signed => 0x00,
+            RtpFormat::Unsigned => 0x08,
+        };
+        self.modify_register(registers::CONTROL3, 0x08, bit).await
+    }
+
+    pub async fn set_input_mode(&mut self, mode: InputMode) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        let bit = match mode {
+            InputMode::Unidirectional => 0x00,
+            InputMode::Bidirectional => 0x80,
+        };
+        self.modify_register(registers::CONTROL2, 0x80, bit).await
+    }
+
     pub async fn set_rtp_input(&mut self, value: u8) -> Result<(), Error<E>> {
         self.write_register(registers::RTP_INPUT, value).await
     }
 
+    pub async fn get_rtp_input(&mut self) -> Result<u8, Error<E>> {
+        self.read_register(registers::RTP_INPUT).await
+    }
+
+    /// Drives `value` in RTP mode. It is read in the format chosen with
+    /// `set_rtp_data_format`; the chip resets to [`RtpFormat::Signed`].
     pub async fn play_rtp(&mut self, value: u8) -> Result<(), Error<E>> {
-        self.set_mode(Mode::RealTimePlayback).await?;
+        self.prepare_playback(Mode::RealTimePlayback).await?;
         self.set_rtp_input(value).await
     }
 
-    async fn write_register(&mut self, reg: u8, value: u8) -> Result<(), Error<E>> {
-        self.i2c
-            .write(DRV2605L_ADDR, &[reg, value])
-            .await
-            .map_err(Error::I2c)
+    /// Holds `amplitude` in RTP mode until changed or stopped, leaving standby if needed.
+    pub async fn set_continuous(&mut self, amplitude: u8) -> Result<(), Error<E>> {
+        let current = self.read_register(registers::MODE).await?;
+        if current & 0x47 != Mode::RealTimePlayback as u8 {
+            self.resolve_conflict().await?;
+            self.write_register(registers::MODE, Mode::RealTimePlayback as u8)
+                .await?;
+        }
+        self.set_rtp_input(amplitude).await
     }
 
-    async fn read_register(&mut self, reg: u8) -> Result<u8, Error<E>> {
-        let mut buf = [0u8; 1];
-        self.i2c
-            .write_read(DRV2605L_ADDR, &[reg], &mut buf)
-            .await
-            .map_err(Error::I2c)?;
-        Ok(buf[0])
+    /// Feeds `level` through `meter` and writes the smoothed amplitude. Expects the
+    /// device to already be in RTP mode, e.g. via `set_continuous(0)`.
+    pub async fn set_level(&mut self, meter: &mut LevelMeter, level: u8) -> Result<(), Error<E>> {
+        let amplitude = meter.update(level);
+        self.set_rtp_input(amplitude).await
     }
 
-    pub async fn set_rated_voltage(&mut self, mv: u16) -> Result<(), Error<E>> {
-        let value = ((mv as u32 * 255) / 5600) as u8;
-        self.write_register(registers::RATED_VOLTAGE, value).await
+    /// Configures a plain open-loop ERM driven at `drive_mv` full scale, with no
+    /// library selected, ready for RTP amplitude writes.
+    pub async fn setup_erm_dc(&mut self, drive_mv: u16) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        self.track_motor_type(MotorType::ERM);
+        self.modify_register(registers::FEEDBACK_CONTROL, 0x80, 0x00)
+            .await?;
+        self.set_library(Library::Empty).await?;
+        self.set_loop_mode(LoopMode::OpenLoop).await?;
+        self.set_rated_voltage(drive_mv).await?;
+        self.set_open_loop_drive_voltage(drive_mv).await?;
+        self.set_continuous(0).await
     }
 
-    pub async fn set_overdrive_voltage(&mut self, mv: u16) -> Result<(), Error<E>> {
-        let value = ((mv as u32 * 255) / 5600) as u8;
-        self.write_register(registers::OVERDRIVE_CLAMP_VOLTAGE, value)
+    /// Writes `count` samples produced by `next_sample` to RTP, one every `period`,
+    /// then returns RTP to zero.
+    pub async fn play_rtp_stream(
+        &mut self,
+        next_sample: impl FnMut() -> u8,
+        period: Duration,
+        count: usize,
+    ) -> Result<(), Error<E>> {
+        self.stream_rtp(core::iter::repeat_with(next_sample).take(count), period)
             .await
     }
 
-    pub async fn get_device_id(&mut self) -> Result<u8, Error<E>> {
-        let status = self.read_register(registers::STATUS).await?;
-        Ok((status >> 5) & 0x07)
+    /// Writes each of `samples` to RTP, one every `sample_period`, then returns
+    /// RTP to zero. Pass `buffer.iter().copied()` to play a recorded buffer.
+    pub async fn stream_rtp(
+        &mut self,
+        samples: impl IntoIterator<Item = u8>,
+        sample_period: Duration,
+    ) -> Result<(), Error<E>> {
+        self.prepare_playback(Mode::RealTimePlayback).await?;
+
+        for sample in samples {
+            self.set_rtp_input(sample).await?;
+            Timer::after(sample_period).await;
+        }
+
+        self.set_rtp_input(0).await
     }
 
-    pub async fn auto_calibrate(&mut self) -> Result<(), Error<E>> {
-        self.set_mode(Mode::AutoCalibration).await?;
-        self.go().await?;
+    /// Sweeps RTP linearly from `from` to `to` over `duration` in `steps` equal
+    /// steps, leaving it at `to`.
+    pub async fn ramp_rtp(
+        &mut self,
+        from: u8,
+        to: u8,
+        duration: Duration,
+        steps: u16,
+    ) -> Result<(), Error<E>> {
+        if steps == 0 {
+            return Err(Error::InvalidParameter);
+        }
 
-        // Wait for calibration to complete
-        let mut timeout = 100;
-        while self.is_playing().await? && timeout > 0 {
-            Timer::after(Duration::from_millis(10)).await;
-            timeout -= 1;
+        self.prepare_playback(Mode::RealTimePlayback).await?;
+        self.set_rtp_input(from).await?;
+
+        let interval = duration / steps as u32;
+        for step in 1..=steps {
+            Timer::after(interval).await;
+            self.set_rtp_input(ramp_value(from, to, step, steps))
+                .await?;
         }
+        Ok(())
+    }
 
-        if timeout == 0 {
-            return Err(Error::CalibrationFailed);
+    /// Plays a phone-style vibration pattern of on/off durations at `amplitude`,
+    /// ending with RTP at zero.
+    pub async fn play_pattern(
+        &mut self,
+        pattern: &[(bool, Duration)],
+        amplitude: u8,
+    ) -> Result<(), Error<E>> {
+        self.prepare_playback(Mode::RealTimePlayback).await?;
+
+        for &(on, duration) in pattern {
+            self.set_rtp_input(if on { amplitude } else { 0 }).await?;
+            Timer::after(duration).await;
         }
 
-        // Check if calibration was successful
-        let status = self.read_register(registers::STATUS).await?;
-        if status & 0x08 != 0 {
-            return Err(Error::CalibrationFailed);
+        self.set_rtp_input(0).await
+    }
+
+    pub async fn play_envelope(&mut self, envelope: &RtpEnvelope<'_>) -> Result<(), Error<E>> {
+        let tick = envelope.tick();
+        if tick.is_zero() {
+            return Err(Error::InvalidParameter);
+        }
+        let tick_timer = Duration::try_from(tick).map_err(|_| Error::InvalidParameter)?;
+
+        self.prepare_playback(Mode::RealTimePlayback).await?;
+
+        let end = envelope.duration();
+        let mut t = core::time::Duration::ZERO;
+        loop {
+            self.set_rtp_input(envelope.amplitude_at(t)).await?;
+            if t >= end {
+                break;
+            }
+            Timer::after(tick_timer).await;
+            t = t.saturating_add(tick);
+        }
+
+        self.set_rtp_input(0).await
+    }
+
+    /// Buzzes `text` as Morse code in RTP mode: dot = 1 unit, dash = 3, with 1 unit
+    /// between symbols, 3 between characters and 7 between words. `amplitude` is
+    /// written to RTP as-is, so full scale is `0x7F` with `RtpFormat::Signed` and
+    /// `0xFF` with `RtpFormat::Unsigned`.
+    pub async fn play_morse(
+        &mut self,
+        text: &str,
+        unit: Duration,
+        amplitude: u8,
+    ) -> Result<(), Error<E>> {
+        if text.chars().any(|c| c != ' ' && morse_code(c).is_none()) {
+            return Err(Error::InvalidParameter);
+        }
+
+        self.prepare_playback(Mode::RealTimePlayback).await?;
+
+        let mut gap = 0;
+        for c in text.chars() {
+            let Some(code) = morse_code(c) else {
+                if gap > 0 {
+                    gap = 7;
+                }
+                continue;
+            };
+
+            if gap > 0 {
+                Timer::after(unit * gap).await;
+            }
+            for (i, symbol) in code.bytes().enumerate() {
+                if i > 0 {
+                    Timer::after(unit).await;
+                }
+                self.set_rtp_input(amplitude).await?;
+                Timer::after(if symbol == b'-' { unit * 3 } else { unit }).await;
+                self.set_rtp_input(0).await?;
+            }
+            gap = 3;
         }
 
         Ok(())
     }
+
+    async fn resolve_conflict(&mut self) -> Result<(), Error<E>> {
+        if self.is_playing().await? {
+            match self.config.playback_conflict {
+                ConflictPolicy::Stop => self.stop().await?,
+                ConflictPolicy::Busy => return Err(Error::Busy),
+            }
+        }
+        Ok(())
+    }
+
+    async fn prepare_playback(&mut self, mode: Mode) -> Result<(), Error<E>> {
+        self.resolve_conflict().await?;
+        self.set_mode(mode).await
+    }
+
+    /// Writes `steps` from slot 0, terminating the sequence if slots remain.
+    async fn load_sequence(&mut self, steps: &[u8]) -> Result<(), Error<E>> {
+        if steps.len() > 8 {
+            return Err(Error::InvalidParameter);
+        }
+
+        let mut slots = [0u8; 8];
+        slots[..steps.len()].copy_from_slice(steps);
+        let len = (steps.len() + 1).min(8);
+        self.write_registers(registers::WAVEFORM_SEQUENCER_1, &slots[..len])
+            .await
+    }
+
+    fn encode_pattern(pattern: &[(Effect, Duration)]) -> Result<([u8; 8], usize), Error<E>> {
+        encode_timed_sequence(
+            pattern
+                .iter()
+                .map(|&(effect, gap)| (effect, u32::try_from(gap.as_millis()).unwrap_or(u32::MAX))),
+        )
+        .ok_or(Error::InvalidParameter)
+    }
+
+    /// Drives amplitude from a PWM signal on IN/TRIG, which the host must
+    /// generate; nothing is sent over I2C per sample. Clears N_PWM_ANALOG and
+    /// enters [`Mode::PwmOrAnalogInput`]. Rewrites Control3, so it returns
+    /// `Error::ConfigLocked` while the configuration is locked.
+    pub async fn enter_pwm_mode(&mut self) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        self.resolve_conflict().await?;
+        self.modify_register(registers::CONTROL3, 0x02, 0x00)
+            .await?;
+        self.set_mode(Mode::PwmOrAnalogInput).await
+    }
+
+    /// Drives amplitude from a DC level on IN/TRIG, e.g. a DAC output. Sets
+    /// N_PWM_ANALOG and enters [`Mode::PwmOrAnalogInput`]. AC_COUPLE must be off
+    /// for a DC level, so it is cleared as well. Locked like `enter_pwm_mode`.
+    pub async fn enter_analog_mode(&mut self) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        self.resolve_conflict().await?;
+        self.modify_register(registers::CONTROL1, 0x20, 0x00)
+            .await?;
+        self.modify_register(registers::CONTROL3, 0x02, 0x02)
+            .await?;
+        self.set_mode(Mode::PwmOrAnalogInput).await
+    }
+
+    /// Switches to audio-to-vibe: AC-couples and selects analog input on IN/TRIG,
+    /// then enters mode 0x04. The ATH registers reset to a 20 ms peak time, a
+    /// 125 Hz filter, a 0.18–1.8 V input range and 10–100 % drive. Locked like
+    /// `enter_pwm_mode`, since it sets AC_COUPLE and N_PWM_ANALOG.
+    pub async fn enter_audio_to_vibe(&mut self) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        self.resolve_conflict().await?;
+        self.modify_register(registers::CONTROL1, 0x20, 0x20)
+            .await?;
+        self.modify_register(registers::CONTROL3, 0x02, 0x02)
+            .await?;
+        self.set_mode(Mode::AudioToVibe).await
+    }
+
+    /// Input levels mapped to minimum and maximum drive, up to 1.8 V.
+    pub async fn set_audio_input_range(
+        &mut self,
+        min_mv: u16,
+        max_mv: u16,
+    ) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        let (Some(min), Some(max)) = (audio_input_code(min_mv), audio_input_code(max_mv)) else {
+            return Err(Error::InvalidParameter);
+        };
+        if min >= max {
+            return Err(Error::InvalidParameter);
+        }
+        self.write_registers(registers::AUDIO_TO_VIBE_MIN_INPUT, &[min, max])
+            .await
+    }
+
+    /// Drive range, in percent of full scale, the input range maps onto.
+    pub async fn set_audio_output_range(
+        &mut self,
+        min_pct: u8,
+        max_pct: u8,
+    ) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        let (Some(min), Some(max)) = (audio_drive_code(min_pct), audio_drive_code(max_pct)) else {
+            return Err(Error::InvalidParameter);
+        };
+        if min > max {
+            return Err(Error::InvalidParameter);
+        }
+        self.write_registers(registers::AUDIO_TO_VIBE_MIN_OUTPUT, &[min, max])
+            .await
+    }
+
+    pub async fn set_audio_peak_time(&mut self, time: AudioPeakTime) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        self.modify_register(registers::AUDIO_TO_VIBE_CONTROL, 0x0C, (time as u8) << 2)
+            .await
+    }
+
+    pub async fn set_audio_filter(&mut self, filter: AudioFilter) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        self.modify_register(registers::AUDIO_TO_VIBE_CONTROL, 0x03, filter as u8)
+            .await
+    }
+
+    /// Reads registers 0x00..=0x22 in one burst. Nothing is written, but the read
+    /// of STATUS clears its latched fault flags like any other.
+    pub async fn dump_registers(&mut self) -> Result<RegisterDump, Error<E>> {
+        let mut raw = [0u8; RegisterDump::LEN];
+        self.read_registers(registers::STATUS, &mut raw).await?;
+        Ok(RegisterDump::from_bytes(&raw))
+    }
+
+    /// Writes any register directly, bypassing the typed API and config lock.
+    pub async fn write_raw(&mut self, reg: u8, value: u8) -> Result<(), Error<E>> {
+        self.write_register(reg, value).await
+    }
+
+    pub async fn read_raw(&mut self, reg: u8) -> Result<u8, Error<E>> {
+        self.read_register(reg).await
+    }
+
+    /// Keeps the stored `Config` in step with runtime motor type changes, so
+    /// `init` and `detect_and_recover` restore what is actually in use.
+    fn track_motor_type(&mut self, motor_type: MotorType) {
+        self.motor_type = motor_type;
+        self.config.motor_type = Some(motor_type);
+    }
+
+    /// Library `init` or `set_library` last selected.
+    fn active_library(&self) -> Library {
+        self.config
+            .library
+            .unwrap_or(Library::default_for(self.motor_type))
+    }
+
+    fn ensure_unlocked(&self) -> Result<(), Error<E>> {
+        if self.config_locked {
+            return Err(Error::ConfigLocked);
+        }
+        Ok(())
+    }
+
+    /// Issues the writes a dropped `PlayGuard` or `StandbyGuard` could not send
+    /// itself.
+    async fn flush_pending(&mut self) -> Result<(), Error<E>> {
+        if self.stop_pending {
+            #[cfg(feature = "defmt")]
+            defmt::trace!("drv2605l deferred write {=u8:#04x} <- 0x00", registers::GO);
+            self.i2c
+                .write(self.address, &[registers::GO, 0x00])
+                .await
+                .map_err(Error::I2c)?;
+            self.stop_pending = false;
+        }
+        if self.standby_pending {
+            #[cfg(feature = "defmt")]
+            defmt::trace!(
+                "drv2605l deferred write {=u8:#04x} <- 0x40",
+                registers::MODE
+            );
+            self.i2c
+                .write(self.address, &[registers::MODE, 0x40])
+                .await
+                .map_err(Error::I2c)?;
+            self.standby_pending = false;
+        }
+        Ok(())
+    }
+
+    async fn write_register(&mut self, reg: u8, value: u8) -> Result<(), Error<E>> {
+        self.flush_pending().await?;
+        #[cfg(feature = "defmt")]
+        defmt::trace!("drv2605l write {=u8:#04x} <- {=u8:#04x}", reg, value);
+        let mut attempt = 0;
+        loop {
+            let result = self.i2c.write(self.address, &[reg, value]).await;
+            if result.is_ok() || !self.retry_wait(&mut attempt).await {
+                return result.map_err(Error::I2c);
+            }
+        }
+    }
+
+    /// Writes `values` to consecutive registers from `start` in one transaction.
+    async fn write_registers(&mut self, start: u8, values: &[u8]) -> Result<(), Error<E>> {
+        let mut buf = [0u8; 9];
+        if values.len() >= buf.len() {
+            return Err(Error::InvalidParameter);
+        }
+
+        buf[0] = start;
+        buf[1..=values.len()].copy_from_slice(values);
+        self.flush_pending().await?;
+        #[cfg(feature = "defmt")]
+        defmt::trace!("drv2605l write {=u8:#04x} <- {=[u8]:#04x}", start, values);
+        let mut attempt = 0;
+        loop {
+            let result = self.i2c.write(self.address, &buf[..=values.len()]).await;
+            if result.is_ok() || !self.retry_wait(&mut attempt).await {
+                return result.map_err(Error::I2c);
+            }
+        }
+    }
+
+    async fn read_register(&mut self, reg: u8) -> Result<u8, Error<E>> {
+        let mut buf = [0u8; 1];
+        self.flush_pending().await?;
+        let mut attempt = 0;
+        loop {
+            let result = self.i2c.write_read(self.address, &[reg], &mut buf).await;
+            if result.is_ok() || !self.retry_wait(&mut attempt).await {
+                result.map_err(Error::I2c)?;
+                break;
+            }
+        }
+        #[cfg(feature = "defmt")]
+        defmt::trace!("drv2605l read {=u8:#04x} -> {=u8:#04x}", reg, buf[0]);
+        Ok(buf[0])
+    }
+
+    /// Reads consecutive registers from `start` into `buf` in one transaction.
+    async fn read_registers(&mut self, start: u8, buf: &mut [u8]) -> Result<(), Error<E>> {
+        self.flush_pending().await?;
+        let mut attempt = 0;
+        loop {
+            let result = self.i2c.write_read(self.address, &[start], buf).await;
+            if result.is_ok() || !self.retry_wait(&mut attempt).await {
+                result.map_err(Error::I2c)?;
+                break;
+            }
+        }
+        #[cfg(feature = "defmt")]
+        defmt::trace!("drv2605l read {=u8:#04x} -> {=[u8]:#04x}", start, buf);
+        Ok(())
+    }
+
+    /// Sleeps for the retry backoff and returns true if another attempt is
+    /// allowed under the retry policy.
+    async fn retry_wait(&self, attempt: &mut u8) -> bool {
+        if *attempt >= self.retries {
+            return false;
+        }
+        *attempt += 1;
+        Timer::after(self.retry_backoff).await;
+        true
+    }
+
+    async fn modify_register(&mut self, reg: u8, mask: u8, value: u8) -> Result<(), Error<E>> {
+        let current = self.read_register(reg).await?;
+        self.write_register(reg, (current & !mask) | (value & mask))
+            .await
+    }
+
+    /// SAMPLE_TIME and LRA frequency the rated-voltage scale depends on. ERM
+    /// scaling needs neither, so nothing is read.
+    async fn rated_voltage_scale(&mut self) -> Result<(SampleTime, u16), Error<E>> {
+        if self.motor_type == MotorType::ERM {
+            return Ok((SampleTime::Us300, 0));
+        }
+
+        let control2 = self.read_register(registers::CONTROL2).await?;
+        let mut period = self.read_register(registers::LRA_RESONANCE_PERIOD).await?;
+        if period == 0 {
+            period = self.read_register(registers::LRA_LOOP_PERIOD).await?;
+        }
+        Ok((
+            SampleTime::from_bits(control2 >> 4),
+            lra_period_to_hz(period).unwrap_or(0),
+        ))
+    }
+
+    fn open_loop_bit(&self) -> u8 {
+        match self.motor_type {
+            MotorType::LRA => 0x01,
+            MotorType::ERM => 0x20,
+        }
+    }
+
+    async fn apply_write(&mut self, write: RegisterWrite) -> Result<(), Error<E>> {
+        if write.mask == 0xFF {
+            self.write_register(write.reg, write.value).await
+        } else {
+            self.modify_register(write.reg, write.mask, write.value)
+                .await
+        }
+    }
+
+    /// Sets the closed-loop rated voltage, average for ERM and RMS for LRA. The LRA
+    /// scale uses the programmed SAMPLE_TIME and the measured resonance, falling
+    /// back to the open-loop period before anything has been measured, so set
+    /// those first. Values beyond the register's range are rejected.
+    pub async fn set_rated_voltage(&mut self, mv: u16) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        self.write_rated_voltage(mv).await?;
+        self.config.rated_voltage_mv = Some(mv);
+        Ok(())
+    }
+
+    async fn write_rated_voltage(&mut self, mv: u16) -> Result<(), Error<E>> {
+        let value = self.encode_rated_voltage(mv).await?;
+        self.write_register(registers::RATED_VOLTAGE, value).await
+    }
+
+    async fn encode_rated_voltage(&mut self, mv: u16) -> Result<u8, Error<E>> {
+        let (sample_time, lra_hz) = self.rated_voltage_scale().await?;
+        rated_voltage_code(self.motor_type, mv, sample_time, lra_hz).ok_or(Error::InvalidParameter)
+    }
+
+    /// Sets the peak overdrive clamp, up to 5.6 V. This is the closed-loop
+    /// meaning of OD_CLAMP; in open loop use `set_open_loop_drive_voltage`.
+    pub async fn set_overdrive_voltage(&mut self, mv: u16) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        let value = overdrive_voltage_code(mv).ok_or(Error::InvalidParameter)?;
+        self.write_register(registers::OVERDRIVE_CLAMP_VOLTAGE, value)
+            .await?;
+        self.config = self.config.overdrive_voltage_mv(mv);
+        Ok(())
+    }
+
+    /// Sets the open-loop drive level. In open loop OD_CLAMP (0x17) is not a
+    /// clamp but the drive voltage itself, on a different scale from
+    /// `set_overdrive_voltage`: average for ERM, RMS for LRA at the frequency
+    /// set with `set_open_loop_frequency_hz`, so set that first.
+    pub async fn set_open_loop_drive_voltage(&mut self, mv: u16) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        self.write_open_loop_drive_voltage(mv).await?;
+        self.config = self.config.open_loop_drive_voltage_mv(mv);
+        Ok(())
+    }
+
+    async fn write_open_loop_drive_voltage(&mut self, mv: u16) -> Result<(), Error<E>> {
+        let value = self.encode_open_loop_drive_voltage(mv).await?;
+        self.write_register(registers::OVERDRIVE_CLAMP_VOLTAGE, value)
+            .await
+    }
+
+    async fn encode_open_loop_drive_voltage(&mut self, mv: u16) -> Result<u8, Error<E>> {
+        let ol_lra_hz = self.open_loop_lra_hz().await?;
+        open_loop_drive_code(self.motor_type, mv, ol_lra_hz).ok_or(Error::InvalidParameter)
+    }
+
+    /// Open-loop LRA drive frequency from LRA_LOOP_PERIOD, or 0 for an ERM.
+    async fn open_loop_lra_hz(&mut self) -> Result<u16, Error<E>> {
+        Ok(match self.motor_type {
+            MotorType::LRA => {
+                let period = self.read_register(registers::LRA_LOOP_PERIOD).await?;
+                lra_period_to_hz(period).unwrap_or(0)
+            }
+            MotorType::ERM => 0,
+        })
+    }
+
+    /// Programmed rated voltage in millivolts, on the same scale as
+    /// `set_rated_voltage`. One register step is about 21 mV and the setter
+    /// rounds, so this can differ from what was set by half a step.
+    pub async fn get_rated_voltage(&mut self) -> Result<u16, Error<E>> {
+        let code = self.get_rated_voltage_raw().await?;
+        let (sample_time, lra_hz) = self.rated_voltage_scale().await?;
+        Ok(rated_voltage_mv(self.motor_type, code, sample_time, lra_hz))
+    }
+
+    /// Programmed overdrive clamp in millivolts. One register step is about
+    /// 22 mV and both directions truncate, so this can read up to 22 mV below
+    /// what was set.
+    pub async fn get_overdrive_voltage(&mut self) -> Result<u16, Error<E>> {
+        let code = self.get_overdrive_voltage_raw().await?;
+        Ok(voltage_code_to_mv(code))
+    }
+
+    pub async fn get_rated_voltage_raw(&mut self) -> Result<u8, Error<E>> {
+        self.read_register(registers::RATED_VOLTAGE).await
+    }
+
+    pub async fn get_overdrive_voltage_raw(&mut self) -> Result<u8, Error<E>> {
+        self.read_register(registers::OVERDRIVE_CLAMP_VOLTAGE).await
+    }
+
+    /// Supply voltage the chip measures (VBAT, 5.6 V full scale) in millivolts,
+    /// for scaling overdrive as a battery discharges.
+    pub async fn get_vbat_mv(&mut self) -> Result<u16, Error<E>> {
+        let code = self.read_register(registers::VBAT_VOLTAGE_MONITOR).await?;
+        Ok(voltage_code_to_mv(code))
+    }
+
+    /// Sets the overdrive clamp to `target_mv`, or to the VBAT the chip measures
+    /// if that is lower, so the clamp never asks for more than the supply can
+    /// give. Returns `Error::InvalidParameter` above 5.6 V, like
+    /// `set_overdrive_voltage`.
+    pub async fn set_overdrive_voltage_for_supply(
+        &mut self,
+        target_mv: u16,
+    ) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        if target_mv > OVERDRIVE_FULL_SCALE_MV {
+            return Err(Error::InvalidParameter);
+        }
+        let vbat = self.read_register(registers::VBAT_VOLTAGE_MONITOR).await?;
+        let value = overdrive_clamp_raw(target_mv, voltage_code_to_mv(vbat))
+            .ok_or(Error::InvalidParameter)?;
+        self.write_register(registers::OVERDRIVE_CLAMP_VOLTAGE, value)
+            .await?;
+        self.config = self.config.overdrive_voltage_mv(voltage_code_to_mv(value));
+        Ok(())
+    }
+
+    /// Writes DRIVE_TIME (Control1 bits 4:0), keeping STARTUP_BOOST and AC_COUPLE.
+    /// Values above 31 are masked to 5 bits.
+    pub async fn set_drive_time(&mut self, value: u8) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        self.modify_register(registers::CONTROL1, 0x1F, value & 0x1F)
+            .await
+    }
+
+    pub async fn set_startup_boost(&mut self, enable: bool) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        let bit = if enable { 0x80 } else { 0x00 };
+        self.modify_register(registers::CONTROL1, 0x80, bit).await
+    }
+
+    /// AC-couples the analog input around a 0.9 V common mode; only useful
+    /// with analog input mode.
+    pub async fn set_ac_couple(&mut self, enable: bool) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        let bit = if enable { 0x20 } else { 0x00 };
+        self.modify_register(registers::CONTROL1, 0x20, bit).await
+    }
+
+    /// Programs motor type, open-loop frequency, rated and overdrive voltage and
+    /// drive time for a known actuator. Any of them can be overridden after.
+    pub async fn apply_actuator_preset(&mut self, preset: ActuatorPreset) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        let settings = preset.settings();
+        self.set_motor_type(settings.motor_type).await?;
+        if settings.resonance_hz != 0 {
+            self.set_open_loop_frequency_hz(settings.resonance_hz)
+                .await?;
+        }
+        self.set_rated_voltage(settings.rated_voltage_mv).await?;
+        self.set_overdrive_voltage(settings.overdrive_voltage_mv)
+            .await?;
+        self.set_drive_time_us(settings.drive_time_us).await?;
+        Ok(())
+    }
+
+    /// Sets DRIVE_TIME from microseconds and returns the time actually achieved.
+    /// Steps are 100 µs from 500 µs for LRA and 200 µs from 1 ms for ERM; for an
+    /// LRA aim for about half the resonance period.
+    pub async fn set_drive_time_us(&mut self, drive_time_us: u16) -> Result<u16, Error<E>> {
+        let (base, step) = drive_time_scale(self.motor_type);
+        let code = (drive_time_us.saturating_sub(base) + step / 2) / step;
+        let code = code.min(0x1F);
+        self.set_drive_time(code as u8).await?;
+        Ok(base + code * step)
+    }
+
+    /// Sets the open-loop LRA drive period and returns the period actually
+    /// achieved, clamped to what the 7-bit register can hold.
+    pub async fn set_open_loop_period_us(&mut self, period_us: u16) -> Result<u16, Error<E>> {
+        self.ensure_unlocked()?;
+        if period_us == 0 {
+            return Err(Error::InvalidParameter);
+        }
+
+        let code = us_to_lra_period(period_us);
+        self.write_register(registers::LRA_LOOP_PERIOD, code)
+            .await?;
+        Ok(lra_period_to_us(code))
+    }
+
+    /// Sets the open-loop LRA drive frequency and returns the frequency actually
+    /// achieved after quantising to the 98.46 µs period register.
+    pub async fn set_open_loop_frequency_hz(&mut self, hz: u16) -> Result<u16, Error<E>> {
+        self.ensure_unlocked()?;
+        if hz == 0 {
+            return Err(Error::InvalidParameter);
+        }
+
+        let code = hz_to_lra_period(hz);
+        self.write_register(registers::LRA_LOOP_PERIOD, code)
+            .await?;
+        Ok(lra_period_to_hz(code).unwrap_or(0))
+    }
+
+    /// Resonance period measured by the closed-loop LRA driver, in microseconds.
+    /// Reads zero until an effect has been played in closed loop.
+    pub async fn get_lra_resonance_period(&mut self) -> Result<u16, Error<E>> {
+        let code = self.read_register(registers::LRA_RESONANCE_PERIOD).await?;
+        Ok(lra_period_to_us(code))
+    }
+
+    /// Measured LRA resonance frequency, or `None` if nothing has been measured yet.
+    pub async fn get_lra_resonance_hz(&mut self) -> Result<Option<u16>, Error<E>> {
+        let code = self.read_register(registers::LRA_RESONANCE_PERIOD).await?;
+        Ok(lra_period_to_hz(code))
+    }
+
+    pub async fn read_status(&mut self) -> Result<Status, Error<E>> {
+        let status = self.read_register(registers::STATUS).await?;
+        Ok(Status::from_register(status))
+    }
+
+    /// OVER_TEMP is latched until STATUS is read.
+    pub async fn over_temperature(&mut self) -> Result<bool, Error<E>> {
+        let status = self.read_register(registers::STATUS).await?;
+        Ok(status & 0x02 != 0)
+    }
+
+    /// OC_DETECT is latched until STATUS is read.
+    pub async fn over_current(&mut self) -> Result<bool, Error<E>> {
+        let status = self.read_register(registers::STATUS).await?;
+        Ok(status & 0x01 != 0)
+    }
+
+    /// Checks that a DRV2605L (or DRV2605) answers at the address, so wiring
+    /// faults surface before `init`.
+    pub async fn probe(&mut self) -> Result<(), Error<E>> {
+        match self.get_device_id().await? {
+            3 | 7 => Ok(()),
+            _ => Err(Error::WrongDevice),
+        }
+    }
+
+    pub async fn get_device_id(&mut self) -> Result<u8, Error<E>> {
+        let status = self.read_register(registers::STATUS).await?;
+        Ok((status >> 5) & 0x07)
+    }
+
+    /// Reads DEVICE_ID and names the part, so code relying on the ROM
+    /// libraries can refuse to run on a RAM-only DRV2604.
+    pub async fn identify(&mut self) -> Result<DeviceVariant, Error<E>> {
+        Ok(DeviceVariant::from_device_id(self.get_device_id().await?))
+    }
+
+    /// Runs auto-calibration. Program the inputs first, see the README.
+    pub async fn auto_calibrate(&mut self) -> Result<CalibrationResult, Error<E>> {
+        self.auto_calibrate_with_timeout(CALIBRATION_TIMEOUT, CALIBRATION_POLL)
+            .await
+    }
+
+    /// `auto_calibrate` with its own limit, for actuators that calibrate slowly
+    /// or setups that should give up sooner.
+    pub async fn auto_calibrate_with_timeout(
+        &mut self,
+        timeout: Duration,
+        poll: Duration,
+    ) -> Result<CalibrationResult, Error<E>> {
+        self.set_mode(Mode::AutoCalibration).await?;
+        self.go().await?;
+
+        // Wait for calibration to complete
+        self.wait_until_done(poll, timeout).await?;
+
+        // Check if calibration was successful
+        let status = self.read_register(registers::STATUS).await?;
+        if status & 0x08 != 0 {
+            return Err(Error::CalibrationFailed);
+        }
+
+        let cal = CalibrationResult {
+            comp: self
+                .read_register(registers::AUTO_CALIB_COMP_RESULT)
+                .await?,
+            bemf: self
+                .read_register(registers::AUTO_CALIB_BACK_EMF_RESULT)
+                .await?,
+            bemf_gain: self.get_bemf_gain().await?,
+        };
+        self.calibration = Some(cal);
+        Ok(cal)
+    }
+
+    /// Runs the actuator self-test. See the README for the registers the verdict
+    /// depends on.
+    pub async fn run_diagnostics(&mut self) -> Result<Diagnostics, Error<E>> {
+        self.set_mode(Mode::Diagnostics).await?;
+        self.go().await?;
+        self.wait_until_done(DIAGNOSTICS_POLL_INTERVAL, DIAGNOSTICS_TIMEOUT)
+            .await?;
+
+        let status = self.read_register(registers::STATUS).await?;
+        Ok(Diagnostics {
+            device_passed: FaultMask::from_status(status).is_empty(),
+            diag_result: status & 0x08 != 0,
+        })
+    }
+
+    /// Plays `effect` and returns how long the measured LRA resonance period takes
+    /// to lock, a proxy for motor startup time. The period left over from the
+    /// last playback is read first and ignored until the register changes; after
+    /// that it is polled about once per resonance period, and the time of the
+    /// first of `STARTUP_SETTLE_READS` consecutive reads within one step of each
+    /// other is returned. If it never settles the full playback time is returned
+    /// as an upper bound.
+    ///
+    /// LRA only: an ERM has no resonance feedback to watch, so it returns
+    /// `Error::InvalidParameter`.
+    pub async fn measure_startup_time(&mut self, effect: u8) -> Result<Duration, Error<E>> {
+        if self.motor_type != MotorType::LRA {
+            return Err(Error::InvalidParameter);
+        }
+
+        let stale = self.read_register(registers::LRA_RESONANCE_PERIOD).await?;
+        self.play_waveform(effect).await?;
+        let start = Instant::now();
+
+        let mut changed = false;
+        let mut last = stale;
+        let mut agreeing = 0;
+        let mut settled_at = Duration::from_ticks(0);
+        while self.is_playing().await? {
+            let elapsed = start.elapsed();
+            if elapsed >= PLAYBACK_TIMEOUT {
+                return Err(Error::Timeout);
+            }
+            let period = self.read_register(registers::LRA_RESONANCE_PERIOD).await?;
+            changed |= period != stale;
+            if changed && period != 0 {
+                if agreeing > 0 && period.abs_diff(last) <= STARTUP_PERIOD_TOLERANCE {
+                    agreeing += 1;
+                } else {
+                    agreeing = 1;
+                    settled_at = elapsed;
+                }
+                if agreeing >= STARTUP_SETTLE_READS {
+                    return Ok(settled_at);
+                }
+            } else {
+                agreeing = 0;
+            }
+            last = period;
+            let period_us = match lra_period_to_us(period) {
+                0 => 1000,
+                us => us as u64,
+            };
+            Timer::after(Duration::from_micros(period_us)).await;
+        }
+
+        Ok(start.elapsed())
+    }
+
+    pub async fn apply_calibration(&mut self, cal: &CalibrationResult) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        self.write_calibration(cal).await?;
+        self.calibration = Some(*cal);
+        Ok(())
+    }
+
+    async fn write_calibration(&mut self, cal: &CalibrationResult) -> Result<(), Error<E>> {
+        self.write_register(registers::AUTO_CALIB_COMP_RESULT, cal.comp)
+            .await?;
+        self.write_register(registers::AUTO_CALIB_BACK_EMF_RESULT, cal.bemf)
+            .await?;
+        self.modify_register(registers::FEEDBACK_CONTROL, 0x03, cal.bemf_gain)
+            .await
+    }
+
+    /// BEMF_GAIN (Feedback Control bits 1:0), written by auto-calibration.
+    pub async fn get_bemf_gain(&mut self) -> Result<u8, Error<E>> {
+        let feedback = self.read_register(registers::FEEDBACK_CONTROL).await?;
+        Ok(feedback & 0x03)
+    }
+
+    pub async fn read_compensation_raw(&mut self) -> Result<u8, Error<E>> {
+        self.read_register(registers::AUTO_CALIB_COMP_RESULT).await
+    }
+
+    /// Auto-calibration compensation as a multiplier, `1 + A_CAL_COMP / 255`.
+    pub async fn read_compensation_factor(&mut self) -> Result<f32, Error<E>> {
+        let raw = self.read_compensation_raw().await?;
+        Ok(1.0 + raw as f32 / 255.0)
+    }
+
+    pub async fn read_drive_state(&mut self) -> Result<DriveState, Error<E>> {
+        let rated = self.read_register(registers::RATED_VOLTAGE).await?;
+        let overdrive = self
+            .read_register(registers::OVERDRIVE_CLAMP_VOLTAGE)
+            .await?;
+        let control1 = self.read_register(registers::CONTROL1).await?;
+        let loop_mode = self.get_loop_mode().await?;
+
+        let resonance_hz = if self.motor_type == MotorType::LRA && loop_mode == LoopMode::ClosedLoop
+        {
+            let period = self.read_register(registers::LRA_RESONANCE_PERIOD).await?;
+            lra_period_to_hz(period)
+        } else {
+            None
+        };
+
+        let (sample_time, lra_hz) = self.rated_voltage_scale().await?;
+        let overdrive_voltage_mv = match loop_mode {
+            LoopMode::OpenLoop => {
+                let ol_lra_hz = self.open_loop_lra_hz().await?;
+                open_loop_drive_mv(self.motor_type, overdrive, ol_lra_hz)
+            }
+            LoopMode::ClosedLoop => voltage_code_to_mv(overdrive),
+        };
+
+        Ok(DriveState {
+            rated_voltage_mv: rated_voltage_mv(self.motor_type, rated, sample_time, lra_hz),
+            overdrive_voltage_mv,
+            loop_mode,
+            drive_time_us: drive_time_us(self.motor_type, control1),
+            resonance_hz,
+        })
+    }
+
+    /// Returns the faults currently flagged in STATUS, limited to `mask`.
+    pub async fn read_faults(&mut self, mask: FaultMask) -> Result<FaultMask, Error<E>> {
+        let status = self.read_register(registers::STATUS).await?;
+        Ok(FaultMask::from_status(status).intersection(mask))
+    }
+
+    /// Plays `effect` back to back `cycles` times with `gap` between runs, checking
+    /// STATUS for overtemperature or overcurrent after each one and stopping at
+    /// the first fault.
+    pub async fn stress_test(
+        &mut self,
+        effect: u8,
+        cycles: u32,
+        gap: Duration,
+    ) -> Result<StressReport, Error<E>> {
+        let mut report = StressReport {
+            completed_cycles: 0,
+            fault: None,
+        };
+
+        for _ in 0..cycles {
+            self.play_waveform(effect).await?;
+            self.wait_until_done(PLAYBACK_POLL, PLAYBACK_TIMEOUT)
+                .await?;
+
+            let faults = self
+                .read_faults(FaultMask::OVER_TEMP | FaultMask::OVER_CURRENT)
+                .await?;
+            if !faults.is_empty() {
+                report.fault = Some(faults);
+                return Ok(report);
+            }
+
+            report.completed_cycles += 1;
+            Timer::after(gap).await;
+        }
+
+        Ok(report)
+    }
+
+    /// Polls STATUS every `interval` and calls `on_fault` whenever a fault in `mask`
+    /// is flagged. Only returns on an I2C error.
+    pub async fn monitor(
+        &mut self,
+        mask: FaultMask,
+        interval: Duration,
+        mut on_fault: impl FnMut(FaultMask),
+    ) -> Result<Infallible, Error<E>> {
+        loop {
+            let faults = self.read_faults(mask).await?;
+            if !faults.is_empty() {
+                on_fault(faults);
+            }
+            Timer::after(interval).await;
+        }
+    }
+}
+
+/// Wraps a driver and puts the device into standby once it has been idle for
+/// `idle_timeout`, waking it again on the next `active` call.
+pub struct AutoStandby<I2C> {
+    driver: Drv2605l<I2C>,
+    idle_timeout: Duration,
+    last_activity: Instant,
+    asleep: bool,
+}
+
+impl<I2C, E> AutoStandby<I2C>
+where
+    I2C: I2c<Error = E>,
+{
+    pub fn new(driver: Drv2605l<I2C>, idle_timeout: Duration) -> Self {
+        Self {
+            driver,
+            idle_timeout,
+            last_activity: Instant::now(),
+            asleep: false,
+        }
+    }
+
+    /// Wakes the device if needed, records activity and hands out the driver.
+    pub async fn active(&mut self) -> Result<&mut Drv2605l<I2C>, Error<E>> {
+        if self.asleep {
+            self.driver.exit_standby().await?;
+            self.asleep = false;
+        }
+        self.last_activity = Instant::now();
+        Ok(&mut self.driver)
+    }
+
+    /// Enters standby if the idle timeout has elapsed and nothing is playing.
+    /// Returns whether the device is now in standby.
+    pub async fn update(&mut self) -> Result<bool, Error<E>> {
+        if !self.asleep && self.last_activity.elapsed() >= self.idle_timeout {
+            if self.driver.is_playing().await? {
+                self.last_activity = Instant::now();
+            } else {
+                self.driver.enter_standby().await?;
+                self.asleep = true;
+            }
+        }
+        Ok(self.asleep)
+    }
+
+    /// Resolves once the device has been put into standby; never resolves while
+    /// it already is, so it can sit in a `select` next to the app's event source.
+    pub async fn wait_idle(&mut self) -> Result<(), Error<E>> {
+        if self.asleep {
+            core::future::pending::<()>().await;
+        }
+        while !self.update().await? {
+            Timer::at(self.last_activity + self.idle_timeout).await;
+        }
+        Ok(())
+    }
+
+    pub fn is_asleep(&self) -> bool {
+        self.asleep
+    }
+
+    pub fn into_inner(self) -> Drv2605l<I2C> {
+        self.driver
+    }
+}
+
+/// Playback started by `trigger_guarded`. Ending it with `wait_until_done`,
+/// `stop` or `disarm` is immediate. If it is dropped instead, e.g. because a
+/// `select` cancelled the task, the stop cannot be awaited from `Drop`, so it is
+/// only sent before the driver's next bus transaction. Drop is best-effort: await
+/// `stop` wherever playback must end, and after a cancellation make any call on
+/// the driver (even `is_playing`) to flush the deferred stop.
+pub struct PlayGuard<'a, I2C> {
+    driver: &'a mut Drv2605l<I2C>,
+    armed: bool,
+}
+
+impl<I2C, E> PlayGuard<'_, I2C>
+where
+    I2C: I2c<Error = E>,
+{
+    pub async fn is_playing(&mut self) -> Result<bool, Error<E>> {
+        self.driver.is_playing().await
+    }
+
+    /// Waits for playback to end on its own. On a timeout the guard is dropped
+    /// armed, so the stop is deferred as for any other drop.
+    pub async fn wait_until_done(
+        mut self,
+        poll_interval: Duration,
+        timeout: Duration,
+    ) -> Result<(), Error<E>> {
+        self.driver.wait_until_done(poll_interval, timeout).await?;
+        self.armed = false;
+        Ok(())
+    }
+
+    pub async fn stop(mut self) -> Result<(), Error<E>> {
+        self.armed = false;
+        self.driver.stop().await
+    }
+
+    /// Lets playback run to completion without stopping it.
+    pub fn disarm(mut self) {
+        self.armed = false;
+    }
+}
+
+impl<I2C> Drop for PlayGuard<'_, I2C> {
+    fn drop(&mut self) {
+        if self.armed {
+            self.driver.stop_pending = true;
+        }
+    }
+}
+
+/// Keeps the device out of standby while held, dereferencing to the driver for
+/// playback. `sleep` enters standby immediately. Entering standby cancels
+/// playback, so wait for effects to finish first. If the guard is dropped
+/// instead, standby cannot be awaited from `Drop` and is entered before the
+/// driver's next bus transaction, so drop alone saves no power until then.
+pub struct StandbyGuard<'a, I2C> {
+    driver: &'a mut Drv2605l<I2C>,
+    armed: bool,
+}
+
+impl<I2C, E> StandbyGuard<'_, I2C>
+where
+    I2C: I2c<Error = E>,
+{
+    pub async fn sleep(mut self) -> Result<(), Error<E>> {
+        self.armed = false;
+        self.driver.enter_standby().await
+    }
+}
+
+impl<I2C> Deref for StandbyGuard<'_, I2C> {
+    type Target = Drv2605l<I2C>;
+
+    fn deref(&self) -> &Self::Target {
+        self.driver
+    }
+}
+
+impl<I2C> DerefMut for StandbyGuard<'_, I2C> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.driver
+    }
+}
+
+impl<I2C> Drop for StandbyGuard<'_, I2C> {
+    fn drop(&mut self) {
+        if self.armed {
+            self.driver.standby_pending = true;
+        }
+    }
 }
@@ -0,0 +1,42 @@
+//! Audio-to-vibe settings, the ATH registers 0x11..=0x15.
+
+/// ATH_PEAK_TIME, how long the envelope detector holds a peak.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum AudioPeakTime {
+    Ms10 = 0,
+    #[default]
+    Ms20 = 1,
+    Ms30 = 2,
+    Ms40 = 3,
+}
+
+/// ATH_FILTER, the low-pass corner applied to the audio envelope.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum AudioFilter {
+    Hz100 = 0,
+    #[default]
+    Hz125 = 1,
+    Hz150 = 2,
+    Hz200 = 3,
+}
+
+/// Full scale of the ATH input registers.
+pub const AUDIO_INPUT_FULL_SCALE_MV: u16 = 1800;
+
+/// ATH_MIN_INPUT / ATH_MAX_INPUT code for `mv`, or `None` above 1.8 V.
+pub const fn audio_input_code(mv: u16) -> Option<u8> {
+    if mv > AUDIO_INPUT_FULL_SCALE_MV {
+        return None;
+    }
+    Some(((mv as u32 * 255) / AUDIO_INPUT_FULL_SCALE_MV as u32) as u8)
+}
+
+/// ATH_MIN_DRIVE / ATH_MAX_DRIVE code for `pct` of full drive, or `None` above 100.
+pub const fn audio_drive_code(pct: u8) -> Option<u8> {
+    if pct > 100 {
+        return None;
+    }
+    Some(((pct as u32 * 255) / 100) as u8)
+}
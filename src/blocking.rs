@@ -1,10 +1,39 @@
-use crate::common::{Error, Library, Mode, MotorType, DRV2605L_ADDR};
+use crate::audio::{audio_drive_code, audio_input_code, AudioFilter, AudioPeakTime};
+#[cfg(feature = "alloc")]
+use crate::common::DynamicSequence;
+use crate::common::{
+    drive_time_scale, drive_time_us, encode_timed_sequence, hz_to_lra_period, init_writes,
+    lra_period_to_hz, lra_period_to_us, open_loop_drive_code, open_loop_drive_mv,
+    overdrive_clamp_raw, overdrive_voltage_code, ramp_value, rated_voltage_code, rated_voltage_mv,
+    us_to_lra_period, voltage_code_to_mv, wait_step, ActuatorPreset, AutoCalTime, BlankingTime,
+    BrakeFactor, CalibrationResult, Config, ConflictPolicy, DeviceVariant, Diagnostics, DriveState,
+    Effect, Error, FaultMask, IdissTime, InputMode, LevelMeter, Library, LoopGain, LoopMode, Mode,
+    MotorType, RegisterDump, RegisterWrite, RtpFormat, SampleTime, Status, TriggerEdge,
+    WaveformSequence, WaveformStep, ZcDetTime, DRV2605L_ADDR, OVERDRIVE_FULL_SCALE_MV,
+};
 use crate::registers;
+use core::task::Poll;
+use core::time::Duration;
+use embedded_hal::delay::DelayNs;
 use embedded_hal::i2c::I2c;
 
+// Upper bound for a full 8-slot sequence, including maximum-length waits.
+const PLAYBACK_TIMEOUT: Duration = Duration::from_secs(12);
+const PLAYBACK_POLL: Duration = Duration::from_millis(5);
+// Covers the longest AUTO_CAL_TIME setting with margin.
+const CALIBRATION_TIMEOUT: Duration = Duration::from_secs(2);
+const CALIBRATION_POLL: Duration = Duration::from_millis(10);
+const DIAGNOSTICS_TIMEOUT: Duration = Duration::from_millis(1000);
+const DIAGNOSTICS_POLL_INTERVAL: Duration = Duration::from_millis(10);
+const RESET_TIMEOUT_MS: u32 = 20;
+
 pub struct Drv2605l<I2C> {
     i2c: I2C,
+    address: u8,
     motor_type: MotorType,
+    config: Config,
+    config_locked: bool,
+    calibration: Option<CalibrationResult>,
 }
 
 impl<I2C, E> Drv2605l<I2C>
@@ -12,31 +41,206 @@ where
     I2C: I2c<Error = E>,
 {
     pub fn new(i2c: I2C) -> Self {
+        Self::new_with_config(i2c, Config::default())
+    }
+
+    /// For boards where the part answers somewhere other than `DRV2605L_ADDR`.
+    pub fn new_with_address(i2c: I2C, address: u8) -> Self {
+        let mut driver = Self::new(i2c);
+        driver.address = address;
+        driver
+    }
+
+    pub fn new_with_config(i2c: I2C, config: Config) -> Self {
+        let motor_type = config.motor_type.unwrap_or(MotorType::LRA);
         Self {
             i2c,
-            motor_type: MotorType::LRA,
+            address: DRV2605L_ADDR,
+            motor_type,
+            config: config.motor_type(motor_type),
+            config_locked: false,
+            calibration: None,
         }
     }
 
+    /// Makes configuration setters return `Error::ConfigLocked` until
+    /// `unlock_config`. The PWM, analog and audio-to-vibe entry points count as
+    /// setters because they rewrite Control3; playback methods are unaffected.
+    pub fn lock_config(&mut self) {
+        self.config_locked = true;
+    }
+
+    pub fn unlock_config(&mut self) {
+        self.config_locked = false;
+    }
+
+    pub fn is_config_locked(&self) -> bool {
+        self.config_locked
+    }
+
+    /// Gives back the I2C bus, e.g. to hand it to another device at shutdown.
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+
+    /// Direct access to the bus. Transactions made through it bypass the driver.
+    pub fn inner_mut(&mut self) -> &mut I2C {
+        &mut self.i2c
+    }
+
+    /// Initializes without waiting after the reset, so the caller must ensure the
+    /// device has had 2 ms to come out of reset. Prefer `init_with_delay`.
     pub fn init(&mut self) -> Result<(), Error<E>> {
         self.reset()?;
+        self.configure()
+    }
 
-        // Wait 2ms after reset
-        // In blocking mode, user must handle delay externally
+    pub fn init_with_delay<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), Error<E>> {
+        self.reset()?;
+        delay.delay_ms(2);
+        self.configure()
+    }
 
-        self.exit_standby()?;
+    /// Full power-up sequence: reset, wait for DEV_RESET to self-clear, then
+    /// program the same state as `init`, leaving the device out of standby in
+    /// [`Mode::InternalTrigger`] with the stored motor type applied.
+    pub fn reset_and_init<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), Error<E>> {
+        self.reset()?;
+        let mut waited_ms = 0;
+        loop {
+            delay.delay_ms(1);
+            waited_ms += 1;
+            if self.read_register(registers::MODE)? & 0x80 == 0 {
+                break;
+            }
+            if waited_ms >= RESET_TIMEOUT_MS {
+                return Err(Error::Timeout);
+            }
+        }
+        self.configure()
+    }
 
-        if self.motor_type == MotorType::LRA {
-            self.write_register(registers::FEEDBACK_CONTROL, 0x80)?;
-            self.set_library(Library::LRA)?;
-        } else {
-            self.write_register(registers::FEEDBACK_CONTROL, 0x00)?;
-            self.set_library(Library::LibraryB)?;
+    fn configure(&mut self) -> Result<(), Error<E>> {
+        if let Some(motor_type) = self.config.motor_type {
+            self.motor_type = motor_type;
+        }
+        for write in init_writes(&self.config) {
+            self.apply_write(write)?;
+        }
+        self.apply_config_options()?;
+
+        if self.config.auto_supply_compensation {
+            self.apply_supply_compensation()?;
         }
 
         Ok(())
     }
 
+    /// Resets the chip and programs the whole `Config` in one go: exit standby,
+    /// feedback and library, then voltages. Unlike `init_with_delay` this honors
+    /// `lock_config`.
+    pub fn apply_config<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        self.init_with_delay(delay)
+    }
+
+    /// Re-runs `init_with_delay` if the chip no longer holds the settings `init`
+    /// programmed, e.g. after a brown-out reset, then re-applies the last
+    /// calibration from `auto_calibrate` or `apply_calibration`. Feedback Control
+    /// and the library are always checked, and so are the rated voltage and
+    /// OD_CLAMP when configured; rated voltage is skipped with
+    /// `auto_supply_compensation`, which may have lowered it. Runtime changes made
+    /// through the setters count as programmed. Returns whether recovery was
+    /// needed.
+    pub fn detect_and_recover<D: DelayNs>(&mut self, delay: &mut D) -> Result<bool, Error<E>> {
+        if self.registers_intact()? {
+            return Ok(false);
+        }
+
+        self.init_with_delay(delay)?;
+        if let Some(cal) = self.calibration {
+            self.write_calibration(&cal)?;
+        }
+        Ok(true)
+    }
+
+    fn registers_intact(&mut self) -> Result<bool, Error<E>> {
+        let feedback = self.read_register(registers::FEEDBACK_CONTROL)?;
+        let library = self.read_register(registers::LIBRARY_SELECTION)?;
+        let intact = init_writes(&self.config)
+            .iter()
+            .all(|write| match write.reg {
+                registers::FEEDBACK_CONTROL => feedback & write.mask == write.value & write.mask,
+                registers::LIBRARY_SELECTION => library & write.mask == write.value & write.mask,
+                _ => true,
+            });
+        if !intact {
+            return Ok(false);
+        }
+
+        let config = self.config;
+        if let (Some(mv), false) = (config.rated_voltage_mv, config.auto_supply_compensation) {
+            let expected = self.encode_rated_voltage(mv)?;
+            if self.read_register(registers::RATED_VOLTAGE)? != expected {
+                return Ok(false);
+            }
+        }
+        let od_clamp = match (
+            config.overdrive_voltage_mv,
+            config.open_loop_drive_voltage_mv,
+        ) {
+            (Some(mv), _) => Some(overdrive_voltage_code(mv).ok_or(Error::InvalidParameter)?),
+            (None, Some(mv)) => Some(self.encode_open_loop_drive_voltage(mv)?),
+            (None, None) => None,
+        };
+        if let Some(expected) = od_clamp {
+            if self.read_register(registers::OVERDRIVE_CLAMP_VOLTAGE)? != expected {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Writes the optional `Config` fields directly, so recovery via `init`
+    /// still works while the configuration is locked.
+    fn apply_config_options(&mut self) -> Result<(), Error<E>> {
+        let config = self.config;
+        if let Some(loop_mode) = config.loop_mode {
+            self.write_loop_mode(loop_mode)?;
+        }
+        if let Some(mv) = config.rated_voltage_mv {
+            self.write_rated_voltage(mv)?;
+        }
+        if let Some(mv) = config.overdrive_voltage_mv {
+            let value = overdrive_voltage_code(mv).ok_or(Error::InvalidParameter)?;
+            self.write_register(registers::OVERDRIVE_CLAMP_VOLTAGE, value)?;
+        }
+        if let Some(mv) = config.open_loop_drive_voltage_mv {
+            self.write_open_loop_drive_voltage(mv)?;
+        }
+        Ok(())
+    }
+
+    fn apply_supply_compensation(&mut self) -> Result<(), Error<E>> {
+        let vbat = self.read_register(registers::VBAT_VOLTAGE_MONITOR)?;
+        self.modify_register(registers::CONTROL3, 0x10, 0x00)?;
+
+        // VBAT is on the 5.6 V full-scale code, RATED_VOLTAGE on the motor's own
+        // scale. A supply above the rated range needs no clamp.
+        let (sample_time, lra_hz) = self.rated_voltage_scale()?;
+        let supply_mv = voltage_code_to_mv(vbat);
+        let Some(limit) = rated_voltage_code(self.motor_type, supply_mv, sample_time, lra_hz)
+        else {
+            return Ok(());
+        };
+
+        let rated = self.read_register(registers::RATED_VOLTAGE)?;
+        if rated > limit {
+            self.write_register(registers::RATED_VOLTAGE, limit)?;
+        }
+        Ok(())
+    }
+
     pub fn reset(&mut self) -> Result<(), Error<E>> {
         self.write_register(registers::MODE, 0x80)
     }
@@ -49,31 +253,142 @@ where
         self.write_register(registers::MODE, 0x40)
     }
 
+    /// Switches the operating mode, keeping the standby bit. Leaving RTP or
+    /// audio-to-vibe mode stops playback (clears GO) first, since changing
+    /// mode with GO set can glitch the output.
     pub fn set_mode(&mut self, mode: Mode) -> Result<(), Error<E>> {
         let current = self.read_register(registers::MODE)?;
+        let current_mode = current & 0x07;
+        let streaming =
+            current_mode == Mode::RealTimePlayback as u8 || current_mode == Mode::AudioToVibe as u8;
+        if streaming && current_mode != mode as u8 {
+            self.stop()?;
+        }
+
         let new_value = (current & 0xF8) | (mode as u8);
         self.write_register(registers::MODE, new_value)
     }
 
+    /// Current operating mode, ignoring the standby bit.
+    pub fn get_mode(&mut self) -> Result<Mode, Error<E>> {
+        let current = self.read_register(registers::MODE)?;
+        Mode::try_from(current & 0x07).map_err(|_| Error::InvalidParameter)
+    }
+
     pub fn set_library(&mut self, library: Library) -> Result<(), Error<E>> {
-        self.write_register(registers::LIBRARY_SELECTION, library as u8)
+        self.ensure_unlocked()?;
+        self.write_register(registers::LIBRARY_SELECTION, library as u8)?;
+        self.config.library = Some(library);
+        Ok(())
     }
 
     pub fn set_motor_type(&mut self, motor_type: MotorType) -> Result<(), Error<E>> {
-        self.motor_type = motor_type;
+        self.ensure_unlocked()?;
+        self.track_motor_type(motor_type);
 
         match motor_type {
             MotorType::LRA => {
-                self.write_register(registers::FEEDBACK_CONTROL, 0x80)?;
+                self.modify_register(registers::FEEDBACK_CONTROL, 0x80, 0x80)?;
                 self.set_library(Library::LRA)
             }
             MotorType::ERM => {
-                self.write_register(registers::FEEDBACK_CONTROL, 0x00)?;
+                self.modify_register(registers::FEEDBACK_CONTROL, 0x80, 0x00)?;
                 self.set_library(Library::LibraryB)
             }
         }
     }
 
+    /// Motor type the chip is actually configured for (N_ERM_LRA). The cached
+    /// type used by other methods is updated to match if it had drifted, but the
+    /// stored `Config` is not, so `detect_and_recover` still restores the
+    /// configured type after a reset. A mismatch is logged with defmt.
+    pub fn get_motor_type(&mut self) -> Result<MotorType, Error<E>> {
+        let feedback = self.read_register(registers::FEEDBACK_CONTROL)?;
+        let motor_type = if feedback & 0x80 != 0 {
+            MotorType::LRA
+        } else {
+            MotorType::ERM
+        };
+        #[cfg(feature = "defmt")]
+        if self.config.motor_type != Some(motor_type) {
+            defmt::warn!(
+                "drv2605l N_ERM_LRA reads {} but {} is configured",
+                motor_type,
+                self.config.motor_type
+            );
+        }
+        self.motor_type = motor_type;
+        Ok(motor_type)
+    }
+
+    pub fn set_brake_factor(&mut self, factor: BrakeFactor) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        self.modify_register(registers::FEEDBACK_CONTROL, 0x70, (factor as u8) << 4)?;
+        self.config.brake_factor = Some(factor);
+        Ok(())
+    }
+
+    pub fn set_loop_gain(&mut self, gain: LoopGain) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        self.modify_register(registers::FEEDBACK_CONTROL, 0x0C, (gain as u8) << 2)?;
+        self.config.loop_gain = Some(gain);
+        Ok(())
+    }
+
+    pub fn set_sample_time(&mut self, time: SampleTime) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        self.modify_register(registers::CONTROL2, 0x30, (time as u8) << 4)
+    }
+
+    pub fn set_blanking_time(&mut self, time: BlankingTime) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        self.modify_register(registers::CONTROL2, 0x0C, (time as u8) << 2)
+    }
+
+    pub fn set_idiss_time(&mut self, time: IdissTime) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        self.modify_register(registers::CONTROL2, 0x03, time as u8)
+    }
+
+    /// Longer calibration helps actuators that have not settled when the
+    /// default run ends.
+    pub fn set_auto_cal_time(&mut self, time: AutoCalTime) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        self.modify_register(registers::CONTROL4, 0x30, (time as u8) << 4)
+    }
+
+    pub fn set_zero_cross_det_time(&mut self, time: ZcDetTime) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        self.modify_register(registers::CONTROL4, 0xC0, (time as u8) << 6)
+    }
+
+    /// Sets open or closed loop for the current motor type; ERM and LRA use
+    /// separate bits in Control3.
+    pub fn set_loop_mode(&mut self, loop_mode: LoopMode) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        self.write_loop_mode(loop_mode)?;
+        self.config.loop_mode = Some(loop_mode);
+        Ok(())
+    }
+
+    fn write_loop_mode(&mut self, loop_mode: LoopMode) -> Result<(), Error<E>> {
+        let bit = self.open_loop_bit();
+        let value = match loop_mode {
+            LoopMode::OpenLoop => bit,
+            LoopMode::ClosedLoop => 0x00,
+        };
+        self.modify_register(registers::CONTROL3, bit, value)
+    }
+
+    pub fn get_loop_mode(&mut self) -> Result<LoopMode, Error<E>> {
+        let control3 = self.read_register(registers::CONTROL3)?;
+        if control3 & self.open_loop_bit() != 0 {
+            Ok(LoopMode::OpenLoop)
+        } else {
+            Ok(LoopMode::ClosedLoop)
+        }
+    }
+
     pub fn go(&mut self) -> Result<(), Error<E>> {
         self.write_register(registers::GO, 0x01)
     }
@@ -87,65 +402,866 @@ where
         Ok(go_reg & 0x01 != 0)
     }
 
-    pub fn set_waveform(&mut self, slot: u8, effect: u8) -> Result<(), Error<E>> {
+    /// One GO check for callers driving their own state machine: `Ready`
+    /// once playback or calibration has finished, `Pending` while it runs.
+    pub fn poll_completion(&mut self) -> Result<Poll<()>, Error<E>> {
+        if self.is_playing()? {
+            Ok(Poll::Pending)
+        } else {
+            Ok(Poll::Ready(()))
+        }
+    }
+
+    /// Polls the GO bit every `poll_interval` until playback or calibration
+    /// finishes, failing with `Error::Timeout` once `timeout` has been spent
+    /// waiting. Time is counted from the delays only, not the bus traffic.
+    pub fn wait_until_done<D: DelayNs>(
+        &mut self,
+        poll_interval: Duration,
+        timeout: Duration,
+        delay: &mut D,
+    ) -> Result<(), Error<E>> {
+        let poll_us = u32::try_from(poll_interval.as_micros()).unwrap_or(u32::MAX);
+        let mut waited = Duration::ZERO;
+        while self.is_playing()? {
+            if waited >= timeout {
+                return Err(Error::Timeout);
+            }
+            delay.delay_us(poll_us);
+            waited += Duration::from_micros(u64::from(poll_us.max(1)));
+        }
+        Ok(())
+    }
+
+    pub fn set_waveform(
+        &mut self,
+        slot: u8,
+        step: impl Into<WaveformStep>,
+    ) -> Result<(), Error<E>> {
         if slot > 7 {
             return Err(Error::InvalidParameter);
         }
 
+        let byte = step.into().to_byte().ok_or(Error::InvalidParameter)?;
         let reg = registers::WAVEFORM_SEQUENCER_1 + slot;
-        self.write_register(reg, effect)
+        self.write_registers(reg, &[byte])
+    }
+
+    pub fn set_wait(&mut self, slot: u8, wait: Duration) -> Result<(), Error<E>> {
+        let ms = u32::try_from(wait.as_millis()).unwrap_or(u32::MAX);
+        let step = wait_step(ms).ok_or(Error::InvalidParameter)?;
+        self.set_waveform(slot, step)
     }
 
     pub fn clear_waveform_sequence(&mut self) -> Result<(), Error<E>> {
-        for i in 0..8 {
-            self.set_waveform(i, 0)?;
+        self.write_registers(registers::WAVEFORM_SEQUENCER_1, &[0; 8])
+    }
+
+    /// Typed `set_waveform` that only accepts ROM library effects.
+    pub fn set_effect(&mut self, slot: u8, effect: Effect) -> Result<(), Error<E>> {
+        self.set_waveform(slot, effect)
+    }
+
+    /// Typed `play_waveform` that only accepts ROM library effects.
+    pub fn play_effect(&mut self, effect: Effect) -> Result<(), Error<E>> {
+        self.play_waveform(effect.as_u8())
+    }
+
+    /// Loads `effects` into slots 0.. and terminates the sequence if slots remain,
+    /// without setting GO, so it can be armed for an external trigger.
+    pub fn set_waveform_sequence(&mut self, effects: &[u8]) -> Result<(), Error<E>> {
+        self.load_sequence(effects)
+    }
+
+    pub fn play_waveform_sequence(&mut self, effects: &[u8]) -> Result<(), Error<E>> {
+        if effects.len() > 8 {
+            return Err(Error::InvalidParameter);
         }
-        Ok(())
+
+        self.prepare_playback(Mode::InternalTrigger)?;
+        self.set_waveform_sequence(effects)?;
+        self.go()
     }
 
+    /// Hands the sequence already loaded in the sequencer (see
+    /// `set_waveform_sequence`) to the IN/TRIG pin without setting GO.
+    pub fn arm_external_trigger(&mut self, edge: TriggerEdge) -> Result<(), Error<E>> {
+        let mode = match edge {
+            TriggerEdge::Edge => Mode::ExternalTriggerEdge,
+            TriggerEdge::Level => Mode::ExternalTriggerLevel,
+        };
+        self.prepare_playback(mode)
+    }
+
+    /// Effect 0 is the sequence terminator and is rejected without touching GO.
     pub fn play_waveform(&mut self, effect: u8) -> Result<(), Error<E>> {
-        self.set_mode(Mode::InternalTrigger)?;
+        if effect == 0 {
+            return Err(Error::InvalidParameter);
+        }
+
+        self.prepare_playback(Mode::InternalTrigger)?;
+        self.write_registers(
+            registers::WAVEFORM_SEQUENCER_1,
+            &[effect, 0, 0, 0, 0, 0, 0, 0],
+        )?;
+        self.go()
+    }
+
+    /// Plays `effect` and blocks until GO clears, returning `Error::Timeout` if
+    /// it is still set after the longest possible sequence.
+    pub fn play_waveform_blocking<D: DelayNs>(
+        &mut self,
+        effect: u8,
+        delay: &mut D,
+    ) -> Result<(), Error<E>> {
+        self.play_waveform(effect)?;
+        self.wait_until_done(PLAYBACK_POLL, PLAYBACK_TIMEOUT, delay)
+    }
+
+    /// Plays `effect`, waits for it to finish plus the selected library's
+    /// longest brake time so the brake phase is not cut short, then enters
+    /// standby. Use `play_waveform_then_standby_with_guard` to pick the margin.
+    pub fn play_waveform_then_standby<D: DelayNs>(
+        &mut self,
+        effect: u8,
+        delay: &mut D,
+    ) -> Result<(), Error<E>> {
+        let guard = self.active_library().max_brake_time();
+        self.play_waveform_then_standby_with_guard(effect, guard, delay)
+    }
+
+    /// `play_waveform_then_standby` with an explicit `guard` after GO clears.
+    pub fn play_waveform_then_standby_with_guard<D: DelayNs>(
+        &mut self,
+        effect: u8,
+        guard: Duration,
+        delay: &mut D,
+    ) -> Result<(), Error<E>> {
+        self.play_waveform(effect)?;
+        self.wait_until_done(PLAYBACK_POLL, PLAYBACK_TIMEOUT, delay)?;
+        delay.delay_us(u32::try_from(guard.as_micros()).unwrap_or(u32::MAX));
+        self.enter_standby()
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn play_dynamic_sequence<D: DelayNs>(
+        &mut self,
+        sequence: &DynamicSequence,
+        delay: &mut D,
+    ) -> Result<(), Error<E>> {
+        self.prepare_playback(Mode::InternalTrigger)?;
+
+        for (i, chunk) in sequence.steps().chunks(8).enumerate() {
+            if i > 0 {
+                self.wait_until_done(PLAYBACK_POLL, PLAYBACK_TIMEOUT, delay)?;
+            }
+            self.load_sequence(chunk)?;
+            self.go()?;
+        }
+
+        Ok(())
+    }
+
+    pub fn play_sequence(&mut self, sequence: &WaveformSequence) -> Result<(), Error<E>> {
+        if !sequence.is_valid() {
+            return Err(Error::InvalidParameter);
+        }
+
+        self.prepare_playback(Mode::InternalTrigger)?;
         self.clear_waveform_sequence()?;
-        self.set_waveform(0, effect)?;
-        self.set_waveform(1, 0)?;
+        self.load_sequence(sequence.steps())?;
+        self.go()
+    }
+
+    /// Number of sequencer slots left free after `pattern`, or
+    /// `Error::InvalidParameter` if it does not fit `play_timed_sequence`.
+    pub fn sequence_capacity(&self, pattern: &[(Effect, Duration)]) -> Result<u8, Error<E>> {
+        let (_, len) = Self::encode_pattern(pattern)?;
+        Ok(8 - len as u8)
+    }
+
+    /// Programs effects separated by hardware wait slots so the chip paces the
+    /// whole pattern itself after a single GO. A zero gap adds no wait slot.
+    pub fn play_timed_sequence(&mut self, pattern: &[(Effect, Duration)]) -> Result<(), Error<E>> {
+        let (steps, len) = Self::encode_pattern(pattern)?;
+
+        self.prepare_playback(Mode::InternalTrigger)?;
+        self.load_sequence(&steps[..len])?;
         self.go()
     }
 
+    /// Stops the running effect and starts `effect` in its place using three
+    /// transactions. Assumes the device is already in internal trigger mode.
+    /// Effect 0 is the sequence terminator and is rejected.
+    pub fn replace_effect(&mut self, effect: u8) -> Result<(), Error<E>> {
+        if effect == 0 {
+            return Err(Error::InvalidParameter);
+        }
+
+        self.stop()?;
+        self.write_registers(registers::WAVEFORM_SEQUENCER_1, &[effect, 0])?;
+        self.go()
+    }
+
+    pub fn set_rtp_data_format(&mut self, format: RtpFormat) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        let bit = match format {
+            RtpFormat::Signed => 0x00,
+            RtpFormat::Unsigned => 0x08,
+        };
+        self.modify_register(registers::CONTROL3, 0x08, bit)
+    }
+
+    pub fn set_input_mode(&mut self, mode: InputMode) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        let bit = match mode {
+            InputMode::Unidirectional => 0x00,
+            InputMode::Bidirectional => 0x80,
+        };
+        self.modify_register(registers::CONTROL2, 0x80, bit)
+    }
+
     pub fn set_rtp_input(&mut self, value: u8) -> Result<(), Error<E>> {
         self.write_register(registers::RTP_INPUT, value)
     }
 
+    pub fn get_rtp_input(&mut self) -> Result<u8, Error<E>> {
+        self.read_register(registers::RTP_INPUT)
+    }
+
+    /// Drives `value` in RTP mode. It is read in the format chosen with
+    /// `set_rtp_data_format`; the chip resets to [`RtpFormat::Signed`].
     pub fn play_rtp(&mut self, value: u8) -> Result<(), Error<E>> {
-        self.set_mode(Mode::RealTimePlayback)?;
+        self.prepare_playback(Mode::RealTimePlayback)?;
         self.set_rtp_input(value)
     }
 
+    /// Sweeps RTP linearly from `from` to `to` over `duration` in `steps` equal
+    /// steps, leaving it at `to`.
+    pub fn ramp_rtp<D: DelayNs>(
+        &mut self,
+        from: u8,
+        to: u8,
+        duration: Duration,
+        steps: u16,
+        delay: &mut D,
+    ) -> Result<(), Error<E>> {
+        if steps == 0 {
+            return Err(Error::InvalidParameter);
+        }
+
+        self.prepare_playback(Mode::RealTimePlayback)?;
+        self.set_rtp_input(from)?;
+
+        let interval_us = u32::try_from(duration.as_micros() / steps as u128).unwrap_or(u32::MAX);
+        for step in 1..=steps {
+            delay.delay_us(interval_us);
+            self.set_rtp_input(ramp_value(from, to, step, steps))?;
+        }
+        Ok(())
+    }
+
+    /// Holds `amplitude` in RTP mode until changed or stopped, leaving standby if needed.
+    pub fn set_continuous(&mut self, amplitude: u8) -> Result<(), Error<E>> {
+        let current = self.read_register(registers::MODE)?;
+        if current & 0x47 != Mode::RealTimePlayback as u8 {
+            self.resolve_conflict()?;
+            self.write_register(registers::MODE, Mode::RealTimePlayback as u8)?;
+        }
+        self.set_rtp_input(amplitude)
+    }
+
+    /// Feeds `level` through `meter` and writes the smoothed amplitude. Expects the
+    /// device to already be in RTP mode, e.g. via `set_continuous(0)`.
+    pub fn set_level(&mut self, meter: &mut LevelMeter, level: u8) -> Result<(), Error<E>> {
+        let amplitude = meter.update(level);
+        self.set_rtp_input(amplitude)
+    }
+
+    /// Configures a plain open-loop ERM driven at `drive_mv` full scale, with no
+    /// library selected, ready for RTP amplitude writes.
+    pub fn setup_erm_dc(&mut self, drive_mv: u16) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        self.track_motor_type(MotorType::ERM);
+        self.modify_register(registers::FEEDBACK_CONTROL, 0x80, 0x00)?;
+        self.set_library(Library::Empty)?;
+        self.set_loop_mode(LoopMode::OpenLoop)?;
+        self.set_rated_voltage(drive_mv)?;
+        self.set_open_loop_drive_voltage(drive_mv)?;
+        self.set_continuous(0)
+    }
+
+    fn resolve_conflict(&mut self) -> Result<(), Error<E>> {
+        if self.is_playing()? {
+            match self.config.playback_conflict {
+                ConflictPolicy::Stop => self.stop()?,
+                ConflictPolicy::Busy => return Err(Error::Busy),
+            }
+        }
+        Ok(())
+    }
+
+    fn prepare_playback(&mut self, mode: Mode) -> Result<(), Error<E>> {
+        self.resolve_conflict()?;
+        self.set_mode(mode)
+    }
+
+    /// Writes `steps` from slot 0, terminating the sequence if slots remain.
+    fn load_sequence(&mut self, steps: &[u8]) -> Result<(), Error<E>> {
+        if steps.len() > 8 {
+            return Err(Error::InvalidParameter);
+        }
+
+        let mut slots = [0u8; 8];
+        slots[..steps.len()].copy_from_slice(steps);
+        let len = (steps.len() + 1).min(8);
+        self.write_registers(registers::WAVEFORM_SEQUENCER_1, &slots[..len])
+    }
+
+    fn encode_pattern(pattern: &[(Effect, Duration)]) -> Result<([u8; 8], usize), Error<E>> {
+        encode_timed_sequence(
+            pattern
+                .iter()
+                .map(|&(effect, gap)| (effect, u32::try_from(gap.as_millis()).unwrap_or(u32::MAX))),
+        )
+        .ok_or(Error::InvalidParameter)
+    }
+
+    /// Drives amplitude from a PWM signal on IN/TRIG, which the host must
+    /// generate; nothing is sent over I2C per sample. Clears N_PWM_ANALOG and
+    /// enters [`Mode::PwmOrAnalogInput`]. Rewrites Control3, so it returns
+    /// `Error::ConfigLocked` while the configuration is locked.
+    pub fn enter_pwm_mode(&mut self) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        self.resolve_conflict()?;
+        self.modify_register(registers::CONTROL3, 0x02, 0x00)?;
+        self.set_mode(Mode::PwmOrAnalogInput)
+    }
+
+    /// Drives amplitude from a DC level on IN/TRIG, e.g. a DAC output. Sets
+    /// N_PWM_ANALOG and enters [`Mode::PwmOrAnalogInput`]. AC_COUPLE must be off
+    /// for a DC level, so it is cleared as well. Locked like `enter_pwm_mode`.
+    pub fn enter_analog_mode(&mut self) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        self.resolve_conflict()?;
+        self.modify_register(registers::CONTROL1, 0x20, 0x00)?;
+        self.modify_register(registers::CONTROL3, 0x02, 0x02)?;
+        self.set_mode(Mode::PwmOrAnalogInput)
+    }
+
+    /// Switches to audio-to-vibe: AC-couples and selects analog input on IN/TRIG,
+    /// then enters mode 0x04. The ATH registers reset to a 20 ms peak time, a
+    /// 125 Hz filter, a 0.18–1.8 V input range and 10–100 % drive. Locked like
+    /// `enter_pwm_mode`, since it sets AC_COUPLE and N_PWM_ANALOG.
+    pub fn enter_audio_to_vibe(&mut self) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        self.resolve_conflict()?;
+        self.modify_register(registers::CONTROL1, 0x20, 0x20)?;
+        self.modify_register(registers::CONTROL3, 0x02, 0x02)?;
+        self.set_mode(Mode::AudioToVibe)
+    }
+
+    /// Input levels mapped to minimum and maximum drive, up to 1.8 V.
+    pub fn set_audio_input_range(&mut self, min_mv: u16, max_mv: u16) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        let (Some(min), Some(max)) = (audio_input_code(min_mv), audio_input_code(max_mv)) else {
+            return Err(Error::InvalidParameter);
+        };
+        if min >= max {
+            return Err(Error::InvalidParameter);
+        }
+        self.write_registers(registers::AUDIO_TO_VIBE_MIN_INPUT, &[min, max])
+    }
+
+    /// Drive range, in percent of full scale, the input range maps onto.
+    pub fn set_audio_output_range(&mut self, min_pct: u8, max_pct: u8) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        let (Some(min), Some(max)) = (audio_drive_code(min_pct), audio_drive_code(max_pct)) else {
+            return Err(Error::InvalidParameter);
+        };
+        if min > max {
+            return Err(Error::InvalidParameter);
+        }
+        self.write_registers(registers::AUDIO_TO_VIBE_MIN_OUTPUT, &[min, max])
+    }
+
+    pub fn set_audio_peak_time(&mut self, time: AudioPeakTime) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        self.modify_register(registers::AUDIO_TO_VIBE_CONTROL, 0x0C, (time as u8) << 2)
+    }
+
+    pub fn set_audio_filter(&mut self, filter: AudioFilter) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        self.modify_register(registers::AUDIO_TO_VIBE_CONTROL, 0x03, filter as u8)
+    }
+
+    /// Reads registers 0x00..=0x22 in one burst. Nothing is written, but the read
+    /// of STATUS clears its latched fault flags like any other.
+    pub fn dump_registers(&mut self) -> Result<RegisterDump, Error<E>> {
+        let mut raw = [0u8; RegisterDump::LEN];
+        self.read_registers(registers::STATUS, &mut raw)?;
+        Ok(RegisterDump::from_bytes(&raw))
+    }
+
+    /// Writes any register directly, bypassing the typed API and config lock.
+    pub fn write_raw(&mut self, reg: u8, value: u8) -> Result<(), Error<E>> {
+        self.write_register(reg, value)
+    }
+
+    pub fn read_raw(&mut self, reg: u8) -> Result<u8, Error<E>> {
+        self.read_register(reg)
+    }
+
+    /// Keeps the stored `Config` in step with runtime motor type changes, so
+    /// `init` and `detect_and_recover` restore what is actually in use.
+    fn track_motor_type(&mut self, motor_type: MotorType) {
+        self.motor_type = motor_type;
+        self.config.motor_type = Some(motor_type);
+    }
+
+    /// Library `init` or `set_library` last selected.
+    fn active_library(&self) -> Library {
+        self.config
+            .library
+            .unwrap_or(Library::default_for(self.motor_type))
+    }
+
+    fn ensure_unlocked(&self) -> Result<(), Error<E>> {
+        if self.config_locked {
+            return Err(Error::ConfigLocked);
+        }
+        Ok(())
+    }
+
     fn write_register(&mut self, reg: u8, value: u8) -> Result<(), Error<E>> {
+        #[cfg(feature = "defmt")]
+        defmt::trace!("drv2605l write {=u8:#04x} <- {=u8:#04x}", reg, value);
         self.i2c
-            .write(DRV2605L_ADDR, &[reg, value])
+            .write(self.address, &[reg, value])
+            .map_err(Error::I2c)
+    }
+
+    /// Writes `values` to consecutive registers from `start` in one transaction.
+    fn write_registers(&mut self, start: u8, values: &[u8]) -> Result<(), Error<E>> {
+        let mut buf = [0u8; 9];
+        if values.len() >= buf.len() {
+            return Err(Error::InvalidParameter);
+        }
+
+        buf[0] = start;
+        buf[1..=values.len()].copy_from_slice(values);
+        #[cfg(feature = "defmt")]
+        defmt::trace!("drv2605l write {=u8:#04x} <- {=[u8]:#04x}", start, values);
+        self.i2c
+            .write(self.address, &buf[..=values.len()])
             .map_err(Error::I2c)
     }
 
     fn read_register(&mut self, reg: u8) -> Result<u8, Error<E>> {
         let mut buf = [0u8; 1];
         self.i2c
-            .write_read(DRV2605L_ADDR, &[reg], &mut buf)
+            .write_read(self.address, &[reg], &mut buf)
             .map_err(Error::I2c)?;
+        #[cfg(feature = "defmt")]
+        defmt::trace!("drv2605l read {=u8:#04x} -> {=u8:#04x}", reg, buf[0]);
         Ok(buf[0])
     }
 
+    /// Reads consecutive registers from `start` into `buf` in one transaction.
+    fn read_registers(&mut self, start: u8, buf: &mut [u8]) -> Result<(), Error<E>> {
+        self.i2c
+            .write_read(self.address, &[start], buf)
+            .map_err(Error::I2c)?;
+        #[cfg(feature = "defmt")]
+        defmt::trace!("drv2605l read {=u8:#04x} -> {=[u8]:#04x}", start, buf);
+        Ok(())
+    }
+
+    fn modify_register(&mut self, reg: u8, mask: u8, value: u8) -> Result<(), Error<E>> {
+        let current = self.read_register(reg)?;
+        self.write_register(reg, (current & !mask) | (value & mask))
+    }
+
+    /// SAMPLE_TIME and LRA frequency the rated-voltage scale depends on. ERM
+    /// scaling needs neither, so nothing is read.
+    fn rated_voltage_scale(&mut self) -> Result<(SampleTime, u16), Error<E>> {
+        if self.motor_type == MotorType::ERM {
+            return Ok((SampleTime::Us300, 0));
+        }
+
+        let control2 = self.read_register(registers::CONTROL2)?;
+        let mut period = self.read_register(registers::LRA_RESONANCE_PERIOD)?;
+        if period == 0 {
+            period = self.read_register(registers::LRA_LOOP_PERIOD)?;
+        }
+        Ok((
+            SampleTime::from_bits(control2 >> 4),
+            lra_period_to_hz(period).unwrap_or(0),
+        ))
+    }
+
+    fn open_loop_bit(&self) -> u8 {
+        match self.motor_type {
+            MotorType::LRA => 0x01,
+            MotorType::ERM => 0x20,
+        }
+    }
+
+    fn apply_write(&mut self, write: RegisterWrite) -> Result<(), Error<E>> {
+        if write.mask == 0xFF {
+            self.write_register(write.reg, write.value)
+        } else {
+            self.modify_register(write.reg, write.mask, write.value)
+        }
+    }
+
+    /// Sets the closed-loop rated voltage, average for ERM and RMS for LRA. The LRA
+    /// scale uses the programmed SAMPLE_TIME and the measured resonance, falling
+    /// back to the open-loop period before anything has been measured, so set
+    /// those first. Values beyond the register's range are rejected.
     pub fn set_rated_voltage(&mut self, mv: u16) -> Result<(), Error<E>> {
-        let value = ((mv as u32 * 255) / 5600) as u8;
+        self.ensure_unlocked()?;
+        self.write_rated_voltage(mv)?;
+        self.config.rated_voltage_mv = Some(mv);
+        Ok(())
+    }
+
+    fn write_rated_voltage(&mut self, mv: u16) -> Result<(), Error<E>> {
+        let value = self.encode_rated_voltage(mv)?;
         self.write_register(registers::RATED_VOLTAGE, value)
     }
 
+    fn encode_rated_voltage(&mut self, mv: u16) -> Result<u8, Error<E>> {
+        let (sample_time, lra_hz) = self.rated_voltage_scale()?;
+        rated_voltage_code(self.motor_type, mv, sample_time, lra_hz).ok_or(Error::InvalidParameter)
+    }
+
+    /// Sets the peak overdrive clamp, up to 5.6 V. This is the closed-loop
+    /// meaning of OD_CLAMP; in open loop use `set_open_loop_drive_voltage`.
     pub fn set_overdrive_voltage(&mut self, mv: u16) -> Result<(), Error<E>> {
-        let value = ((mv as u32 * 255) / 5600) as u8;
+        self.ensure_unlocked()?;
+        let value = overdrive_voltage_code(mv).ok_or(Error::InvalidParameter)?;
+        self.write_register(registers::OVERDRIVE_CLAMP_VOLTAGE, value)?;
+        self.config = self.config.overdrive_voltage_mv(mv);
+        Ok(())
+    }
+
+    /// Sets the open-loop drive level. In open loop OD_CLAMP (0x17) is not a
+    /// clamp but the drive voltage itself, on a different scale from
+    /// `set_overdrive_voltage`: average for ERM, RMS for LRA at the frequency
+    /// set with `set_open_loop_frequency_hz`, so set that first.
+    pub fn set_open_loop_drive_voltage(&mut self, mv: u16) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        self.write_open_loop_drive_voltage(mv)?;
+        self.config = self.config.open_loop_drive_voltage_mv(mv);
+        Ok(())
+    }
+
+    fn write_open_loop_drive_voltage(&mut self, mv: u16) -> Result<(), Error<E>> {
+        let value = self.encode_open_loop_drive_voltage(mv)?;
         self.write_register(registers::OVERDRIVE_CLAMP_VOLTAGE, value)
     }
 
+    fn encode_open_loop_drive_voltage(&mut self, mv: u16) -> Result<u8, Error<E>> {
+        let ol_lra_hz = self.open_loop_lra_hz()?;
+        open_loop_drive_code(self.motor_type, mv, ol_lra_hz).ok_or(Error::InvalidParameter)
+    }
+
+    /// Open-loop LRA drive frequency from LRA_LOOP_PERIOD, or 0 for an ERM.
+    fn open_loop_lra_hz(&mut self) -> Result<u16, Error<E>> {
+        Ok(match self.motor_type {
+            MotorType::LRA => {
+                let period = self.read_register(registers::LRA_LOOP_PERIOD)?;
+                lra_period_to_hz(period).unwrap_or(0)
+            }
+            MotorType::ERM => 0,
+        })
+    }
+
+    /// Programmed rated voltage in millivolts, on the same scale as
+    /// `set_rated_voltage`. One register step is about 21 mV and the setter
+    /// rounds, so this can differ from what was set by half a step.
+    pub fn get_rated_voltage(&mut self) -> Result<u16, Error<E>> {
+        let code = self.get_rated_voltage_raw()?;
+        let (sample_time, lra_hz) = self.rated_voltage_scale()?;
+        Ok(rated_voltage_mv(self.motor_type, code, sample_time, lra_hz))
+    }
+
+    /// Programmed overdrive clamp in millivolts. One register step is about
+    /// 22 mV and both directions truncate, so this can read up to 22 mV below
+    /// what was set.
+    pub fn get_overdrive_voltage(&mut self) -> Result<u16, Error<E>> {
+        let code = self.get_overdrive_voltage_raw()?;
+        Ok(voltage_code_to_mv(code))
+    }
+
+    pub fn get_rated_voltage_raw(&mut self) -> Result<u8, Error<E>> {
+        self.read_register(registers::RATED_VOLTAGE)
+    }
+
+    pub fn get_overdrive_voltage_raw(&mut self) -> Result<u8, Error<E>> {
+        self.read_register(registers::OVERDRIVE_CLAMP_VOLTAGE)
+    }
+
+    /// Supply voltage the chip measures (VBAT, 5.6 V full scale) in millivolts,
+    /// for scaling overdrive as a battery discharges.
+    pub fn get_vbat_mv(&mut self) -> Result<u16, Error<E>> {
+        let code = self.read_register(registers::VBAT_VOLTAGE_MONITOR)?;
+        Ok(voltage_code_to_mv(code))
+    }
+
+    /// Sets the overdrive clamp to `target_mv`, or to the VBAT the chip measures
+    /// if that is lower, so the clamp never asks for more than the supply can
+    /// give. Returns `Error::InvalidParameter` above 5.6 V, like
+    /// `set_overdrive_voltage`.
+    pub fn set_overdrive_voltage_for_supply(&mut self, target_mv: u16) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        if target_mv > OVERDRIVE_FULL_SCALE_MV {
+            return Err(Error::InvalidParameter);
+        }
+        let vbat = self.read_register(registers::VBAT_VOLTAGE_MONITOR)?;
+        let value = overdrive_clamp_raw(target_mv, voltage_code_to_mv(vbat))
+            .ok_or(Error::InvalidParameter)?;
+        self.write_register(registers::OVERDRIVE_CLAMP_VOLTAGE, value)?;
+        self.config = self.config.overdrive_voltage_mv(voltage_code_to_mv(value));
+        Ok(())
+    }
+
+    /// Writes DRIVE_TIME (Control1 bits 4:0), keeping STARTUP_BOOST and AC_COUPLE.
+    /// Values above 31 are masked to 5 bits.
+    pub fn set_drive_time(&mut self, value: u8) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        self.modify_register(registers::CONTROL1, 0x1F, value & 0x1F)
+    }
+
+    pub fn set_startup_boost(&mut self, enable: bool) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        let bit = if enable { 0x80 } else { 0x00 };
+        self.modify_register(registers::CONTROL1, 0x80, bit)
+    }
+
+    /// AC-couples the analog input around a 0.9 V common mode; only useful
+    /// with analog input mode.
+    pub fn set_ac_couple(&mut self, enable: bool) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        let bit = if enable { 0x20 } else { 0x00 };
+        self.modify_register(registers::CONTROL1, 0x20, bit)
+    }
+
+    /// Programs motor type, open-loop frequency, rated and overdrive voltage and
+    /// drive time for a known actuator. Any of them can be overridden after.
+    pub fn apply_actuator_preset(&mut self, preset: ActuatorPreset) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        let settings = preset.settings();
+        self.set_motor_type(settings.motor_type)?;
+        if settings.resonance_hz != 0 {
+            self.set_open_loop_frequency_hz(settings.resonance_hz)?;
+        }
+        self.set_rated_voltage(settings.rated_voltage_mv)?;
+        self.set_overdrive_voltage(settings.overdrive_voltage_mv)?;
+        self.set_drive_time_us(settings.drive_time_us)?;
+        Ok(())
+    }
+
+    /// Sets DRIVE_TIME from microseconds and returns the time actually achieved.
+    /// Steps are 100 µs from 500 µs for LRA and 200 µs from 1 ms for ERM; for an
+    /// LRA aim for about half the resonance period.
+    pub fn set_drive_time_us(&mut self, drive_time_us: u16) -> Result<u16, Error<E>> {
+        let (base, step) = drive_time_scale(self.motor_type);
+        let code = (drive_time_us.saturating_sub(base) + step / 2) / step;
+        let code = code.min(0x1F);
+        self.set_drive_time(code as u8)?;
+        Ok(base + code * step)
+    }
+
+    /// Sets the open-loop LRA drive period and returns the period actually
+    /// achieved, clamped to what the 7-bit register can hold.
+    pub fn set_open_loop_period_us(&mut self, period_us: u16) -> Result<u16, Error<E>> {
+        self.ensure_unlocked()?;
+        if period_us == 0 {
+            return Err(Error::InvalidParameter);
+        }
+
+        let code = us_to_lra_period(period_us);
+        self.write_register(registers::LRA_LOOP_PERIOD, code)?;
+        Ok(lra_period_to_us(code))
+    }
+
+    /// Sets the open-loop LRA drive frequency and returns the frequency actually
+    /// achieved after quantising to the 98.46 µs period register.
+    pub fn set_open_loop_frequency_hz(&mut self, hz: u16) -> Result<u16, Error<E>> {
+        self.ensure_unlocked()?;
+        if hz == 0 {
+            return Err(Error::InvalidParameter);
+        }
+
+        let code = hz_to_lra_period(hz);
+        self.write_register(registers::LRA_LOOP_PERIOD, code)?;
+        Ok(lra_period_to_hz(code).unwrap_or(0))
+    }
+
+    /// Resonance period measured by the closed-loop LRA driver, in microseconds.
+    /// Reads zero until an effect has been played in closed loop.
+    pub fn get_lra_resonance_period(&mut self) -> Result<u16, Error<E>> {
+        let code = self.read_register(registers::LRA_RESONANCE_PERIOD)?;
+        Ok(lra_period_to_us(code))
+    }
+
+    /// Measured LRA resonance frequency, or `None` if nothing has been measured yet.
+    pub fn get_lra_resonance_hz(&mut self) -> Result<Option<u16>, Error<E>> {
+        let code = self.read_register(registers::LRA_RESONANCE_PERIOD)?;
+        Ok(lra_period_to_hz(code))
+    }
+
+    pub fn read_status(&mut self) -> Result<Status, Error<E>> {
+        let status = self.read_register(registers::STATUS)?;
+        Ok(Status::from_register(status))
+    }
+
+    /// OVER_TEMP is latched until STATUS is read.
+    pub fn over_temperature(&mut self) -> Result<bool, Error<E>> {
+        let status = self.read_register(registers::STATUS)?;
+        Ok(status & 0x02 != 0)
+    }
+
+    /// OC_DETECT is latched until STATUS is read.
+    pub fn over_current(&mut self) -> Result<bool, Error<E>> {
+        let status = self.read_register(registers::STATUS)?;
+        Ok(status & 0x01 != 0)
+    }
+
+    /// Checks that a DRV2605L (or DRV2605) answers at the address, so wiring
+    /// faults surface before `init`.
+    pub fn probe(&mut self) -> Result<(), Error<E>> {
+        match self.get_device_id()? {
+            3 | 7 => Ok(()),
+            _ => Err(Error::WrongDevice),
+        }
+    }
+
     pub fn get_device_id(&mut self) -> Result<u8, Error<E>> {
         let status = self.read_register(registers::STATUS)?;
         Ok((status >> 5) & 0x07)
     }
+
+    /// Reads DEVICE_ID and names the part, so code relying on the ROM
+    /// libraries can refuse to run on a RAM-only DRV2604.
+    pub fn identify(&mut self) -> Result<DeviceVariant, Error<E>> {
+        Ok(DeviceVariant::from_device_id(self.get_device_id()?))
+    }
+
+    pub fn apply_calibration(&mut self, cal: &CalibrationResult) -> Result<(), Error<E>> {
+        self.ensure_unlocked()?;
+        self.write_calibration(cal)?;
+        self.calibration = Some(*cal);
+        Ok(())
+    }
+
+    fn write_calibration(&mut self, cal: &CalibrationResult) -> Result<(), Error<E>> {
+        self.write_register(registers::AUTO_CALIB_COMP_RESULT, cal.comp)?;
+        self.write_register(registers::AUTO_CALIB_BACK_EMF_RESULT, cal.bemf)?;
+        self.modify_register(registers::FEEDBACK_CONTROL, 0x03, cal.bemf_gain)
+    }
+
+    /// BEMF_GAIN (Feedback Control bits 1:0), written by auto-calibration.
+    pub fn get_bemf_gain(&mut self) -> Result<u8, Error<E>> {
+        let feedback = self.read_register(registers::FEEDBACK_CONTROL)?;
+        Ok(feedback & 0x03)
+    }
+
+    pub fn read_compensation_raw(&mut self) -> Result<u8, Error<E>> {
+        self.read_register(registers::AUTO_CALIB_COMP_RESULT)
+    }
+
+    /// Auto-calibration compensation as a multiplier, `1 + A_CAL_COMP / 255`.
+    pub fn read_compensation_factor(&mut self) -> Result<f32, Error<E>> {
+        let raw = self.read_compensation_raw()?;
+        Ok(1.0 + raw as f32 / 255.0)
+    }
+
+    /// Runs auto-calibration. Program the inputs first, see the README.
+    pub fn auto_calibrate<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+    ) -> Result<CalibrationResult, Error<E>> {
+        self.auto_calibrate_with_timeout(CALIBRATION_TIMEOUT, CALIBRATION_POLL, delay)
+    }
+
+    /// `auto_calibrate` with its own limit, for actuators that calibrate slowly
+    /// or setups that should give up sooner.
+    pub fn auto_calibrate_with_timeout<D: DelayNs>(
+        &mut self,
+        timeout: Duration,
+        poll: Duration,
+        delay: &mut D,
+    ) -> Result<CalibrationResult, Error<E>> {
+        self.set_mode(Mode::AutoCalibration)?;
+        self.go()?;
+        self.wait_until_done(poll, timeout, delay)?;
+
+        let status = self.read_register(registers::STATUS)?;
+        if status & 0x08 != 0 {
+            return Err(Error::CalibrationFailed);
+        }
+
+        let cal = CalibrationResult {
+            comp: self.read_register(registers::AUTO_CALIB_COMP_RESULT)?,
+            bemf: self.read_register(registers::AUTO_CALIB_BACK_EMF_RESULT)?,
+            bemf_gain: self.get_bemf_gain()?,
+        };
+        self.calibration = Some(cal);
+        Ok(cal)
+    }
+
+    /// Runs the actuator self-test. See the README for the registers the verdict
+    /// depends on.
+    pub fn run_diagnostics<D: DelayNs>(&mut self, delay: &mut D) -> Result<Diagnostics, Error<E>> {
+        self.set_mode(Mode::Diagnostics)?;
+        self.go()?;
+        self.wait_until_done(DIAGNOSTICS_POLL_INTERVAL, DIAGNOSTICS_TIMEOUT, delay)?;
+
+        let status = self.read_register(registers::STATUS)?;
+        Ok(Diagnostics {
+            device_passed: FaultMask::from_status(status).is_empty(),
+            diag_result: status & 0x08 != 0,
+        })
+    }
+
+    pub fn read_drive_state(&mut self) -> Result<DriveState, Error<E>> {
+        let rated = self.read_register(registers::RATED_VOLTAGE)?;
+        let overdrive = self.read_register(registers::OVERDRIVE_CLAMP_VOLTAGE)?;
+        let control1 = self.read_register(registers::CONTROL1)?;
+        let loop_mode = self.get_loop_mode()?;
+
+        let resonance_hz = if self.motor_type == MotorType::LRA && loop_mode == LoopMode::ClosedLoop
+        {
+            let period = self.read_register(registers::LRA_RESONANCE_PERIOD)?;
+            lra_period_to_hz(period)
+        } else {
+            None
+        };
+
+        let (sample_time, lra_hz) = self.rated_voltage_scale()?;
+        let overdrive_voltage_mv = match loop_mode {
+            LoopMode::OpenLoop => {
+                let ol_lra_hz = self.open_loop_lra_hz()?;
+                open_loop_drive_mv(self.motor_type, overdrive, ol_lra_hz)
+            }
+            LoopMode::ClosedLoop => voltage_code_to_mv(overdrive),
+        };
+
+        Ok(DriveState {
+            rated_voltage_mv: rated_voltage_mv(self.motor_type, rated, sample_time, lra_hz),
+            overdrive_voltage_mv,
+            loop_mode,
+            drive_time_us: drive_time_us(self.motor_type, control1),
+            resonance_hz,
+        })
+    }
+
+    /// Returns the faults currently flagged in STATUS, limited to `mask`.
+    pub fn read_faults(&mut self, mask: FaultMask) -> Result<FaultMask, Error<E>> {
+        let status = self.read_register(registers::STATUS)?;
+        Ok(FaultMask::from_status(status).intersection(mask))
+    }
 }
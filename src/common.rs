@@ -1,9 +1,42 @@
+use crate::registers;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "blocking")]
+use embedded_hal::i2c::{Error as I2cError, ErrorKind, NoAcknowledgeSource};
+#[cfg(all(feature = "async", not(feature = "blocking")))]
+use embedded_hal_async::i2c::{Error as I2cError, ErrorKind, NoAcknowledgeSource};
+
 #[derive(Debug, Clone, Copy)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Error<E> {
     I2c(E),
     InvalidParameter,
+    /// Auto-calibration completed but DIAG_RESULT flagged the actuator.
     CalibrationFailed,
+    ConfigLocked,
+    Busy,
+    /// The GO bit never cleared, which usually points at the bus or wiring
+    /// rather than the actuator.
+    Timeout,
+    /// DEVICE_ID in STATUS is not a DRV2605 or DRV2605L.
+    WrongDevice,
+}
+
+#[cfg(any(feature = "async", feature = "blocking"))]
+impl<E: I2cError> Error<E> {
+    /// The bus reported a missing acknowledge, at the address or on data.
+    pub fn is_nack(&self) -> bool {
+        matches!(self, Error::I2c(e) if matches!(e.kind(), ErrorKind::NoAcknowledge(_)))
+    }
+
+    /// Nothing acknowledged the address, i.e. no device answered at all.
+    pub fn is_address_nack(&self) -> bool {
+        matches!(
+            self,
+            Error::I2c(e)
+                if e.kind() == ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address)
+        )
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -19,6 +52,26 @@ pub enum Mode {
     AutoCalibration = 0x07,
 }
 
+impl TryFrom<u8> for Mode {
+    type Error = u8;
+
+    /// Decodes MODE bits 2:0; fails with the original byte if any other bit,
+    /// such as standby or reset, is set.
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0x00 => Ok(Mode::InternalTrigger),
+            0x01 => Ok(Mode::ExternalTriggerEdge),
+            0x02 => Ok(Mode::ExternalTriggerLevel),
+            0x03 => Ok(Mode::PwmOrAnalogInput),
+            0x04 => Ok(Mode::AudioToVibe),
+            0x05 => Ok(Mode::RealTimePlayback),
+            0x06 => Ok(Mode::Diagnostics),
+            0x07 => Ok(Mode::AutoCalibration),
+            _ => Err(value),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum MotorType {
@@ -28,18 +81,503 @@ pub enum MotorType {
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum LoopMode {
+    OpenLoop,
+    ClosedLoop,
+}
+
+/// How the IN/TRIG pin fires an armed sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TriggerEdge {
+    /// A rising edge sets GO and the whole sequence plays.
+    Edge,
+    /// GO follows the pin: the sequence plays while it is high and is
+    /// cancelled when it goes low.
+    Level,
+}
+
+/// How RTP_INPUT is interpreted, DATA_FORMAT_RTP in Control3.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum RtpFormat {
+    /// -128..=127 with 0 as no drive; negative values brake on bidirectional drive.
+    #[default]
+    Signed,
+    /// 0..=255 with 0 as no drive.
+    Unsigned,
+}
+
+/// BIDIR_INPUT in Control2; sets where zero drive sits in the RTP and PWM range.
+///
+/// With [`RtpFormat::Unsigned`], unidirectional maps 0x00 to off and 0xFF to full
+/// drive, while bidirectional puts off at 0x80 and brakes below it. Closed-loop
+/// ERM and LRA drive work in either mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum InputMode {
+    Unidirectional,
+    #[default]
+    Bidirectional,
+}
+
+/// FB_BRAKE_FACTOR field of Feedback Control.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum BrakeFactor {
+    X1 = 0,
+    X2 = 1,
+    X3 = 2,
+    X4 = 3,
+    X6 = 4,
+    X8 = 5,
+    X16 = 6,
+    Disabled = 7,
+}
+
+/// LOOP_GAIN field of Feedback Control.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum LoopGain {
+    Low = 0,
+    Medium = 1,
+    High = 2,
+    VeryHigh = 3,
+}
+
+/// SAMPLE_TIME field of Control2, the LRA back-EMF sampling window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SampleTime {
+    Us150 = 0,
+    Us200 = 1,
+    Us250 = 2,
+    Us300 = 3,
+}
+
+impl SampleTime {
+    pub const fn micros(self) -> u32 {
+        150 + 50 * self as u32
+    }
+
+    #[cfg(any(feature = "async", feature = "blocking"))]
+    pub(crate) const fn from_bits(bits: u8) -> Self {
+        match bits & 0x03 {
+            0 => SampleTime::Us150,
+            1 => SampleTime::Us200,
+            2 => SampleTime::Us250,
+            _ => SampleTime::Us300,
+        }
+    }
+}
+
+/// AUTO_CAL_TIME field of Control4, how long auto-calibration runs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum AutoCalTime {
+    /// 150–350 ms.
+    Ms150 = 0,
+    /// 250–450 ms.
+    Ms250 = 1,
+    /// 500–700 ms, the reset default.
+    Ms500 = 2,
+    /// 1000–1200 ms.
+    Ms1000 = 3,
+}
+
+/// ZC_DET_TIME field of Control4, the minimum time for LRA zero-cross detection.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ZcDetTime {
+    Us100 = 0,
+    Us200 = 1,
+    Us300 = 2,
+    Us390 = 3,
+}
+
+/// BLANKING_TIME field of Control2. The duration depends on the motor type.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum BlankingTime {
+    /// 15 µs LRA, 45 µs ERM.
+    Shortest = 0,
+    /// 25 µs LRA, 75 µs ERM.
+    Short = 1,
+    /// 50 µs LRA, 90 µs ERM.
+    Long = 2,
+    /// 75 µs LRA, 105 µs ERM.
+    Longest = 3,
+}
+
+/// IDISS_TIME field of Control2, the current dissipation time. The duration
+/// depends on the motor type.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum IdissTime {
+    /// 15 µs LRA, 45 µs ERM.
+    Shortest = 0,
+    /// 25 µs LRA, 75 µs ERM.
+    Short = 1,
+    /// 50 µs LRA, 90 µs ERM.
+    Long = 2,
+    /// 75 µs LRA, 105 µs ERM.
+    Longest = 3,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DriveState {
+    pub rated_voltage_mv: u16,
+    /// OD_CLAMP in millivolts. In open loop this is the open-loop drive
+    /// voltage, on the `set_open_loop_drive_voltage` scale.
+    pub overdrive_voltage_mv: u16,
+    pub loop_mode: LoopMode,
+    /// Control1 drive time in microseconds, on the `set_drive_time_us` scale.
+    pub drive_time_us: u16,
+    /// Measured LRA resonance, only available for an LRA in closed loop.
+    pub resonance_hz: Option<u16>,
+}
+
+/// Settings `init` programs after the reset. Options left as `None` keep the
+/// chip's reset defaults, or the motor type's library for `library`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Config {
+    /// Makes `init` read VBAT, enable hardware supply compensation and lower the
+    /// rated voltage to the measured supply if it exceeds it.
+    pub auto_supply_compensation: bool,
+    pub playback_conflict: ConflictPolicy,
+    pub motor_type: Option<MotorType>,
+    pub library: Option<Library>,
+    pub brake_factor: Option<BrakeFactor>,
+    pub loop_gain: Option<LoopGain>,
+    pub loop_mode: Option<LoopMode>,
+    pub rated_voltage_mv: Option<u16>,
+    /// Closed-loop overdrive clamp. Also set by `set_overdrive_voltage_for_supply`.
+    pub overdrive_voltage_mv: Option<u16>,
+    /// Open-loop drive level; takes the place of `overdrive_voltage_mv`, since
+    /// both live in OD_CLAMP.
+    pub open_loop_drive_voltage_mv: Option<u16>,
+}
+
+impl Config {
+    pub const fn new() -> Self {
+        Self {
+            auto_supply_compensation: false,
+            playback_conflict: ConflictPolicy::Stop,
+            motor_type: None,
+            library: None,
+            brake_factor: None,
+            loop_gain: None,
+            loop_mode: None,
+            rated_voltage_mv: None,
+            overdrive_voltage_mv: None,
+            open_loop_drive_voltage_mv: None,
+        }
+    }
+
+    pub const fn auto_supply_compensation(mut self, enable: bool) -> Self {
+        self.auto_supply_compensation = enable;
+        self
+    }
+
+    pub const fn playback_conflict(mut self, policy: ConflictPolicy) -> Self {
+        self.playback_conflict = policy;
+        self
+    }
+
+    pub const fn motor_type(mut self, motor_type: MotorType) -> Self {
+        self.motor_type = Some(motor_type);
+        self
+    }
+
+    pub const fn library(mut self, library: Library) -> Self {
+        self.library = Some(library);
+        self
+    }
+
+    pub const fn brake_factor(mut self, factor: BrakeFactor) -> Self {
+        self.brake_factor = Some(factor);
+        self
+    }
+
+    pub const fn loop_gain(mut self, gain: LoopGain) -> Self {
+        self.loop_gain = Some(gain);
+        self
+    }
+
+    pub const fn loop_mode(mut self, loop_mode: LoopMode) -> Self {
+        self.loop_mode = Some(loop_mode);
+        self
+    }
+
+    pub const fn rated_voltage_mv(mut self, mv: u16) -> Self {
+        self.rated_voltage_mv = Some(mv);
+        self
+    }
+
+    pub const fn overdrive_voltage_mv(mut self, mv: u16) -> Self {
+        self.overdrive_voltage_mv = Some(mv);
+        self.open_loop_drive_voltage_mv = None;
+        self
+    }
+
+    pub const fn open_loop_drive_voltage_mv(mut self, mv: u16) -> Self {
+        self.open_loop_drive_voltage_mv = Some(mv);
+        self.overdrive_voltage_mv = None;
+        self
+    }
+}
+
+/// Starting points for common actuators, for `apply_actuator_preset`. Check
+/// the values against your part's datasheet and run `auto_calibrate` after.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ActuatorPreset {
+    /// Vybronics VLV101040A: 10 mm LRA, 170 Hz, 2.0 V RMS.
+    VybronicsVlv101040a,
+    /// Generic 10 mm coin LRA at 235 Hz, 2.0 V RMS with a 3.2 V peak.
+    CoinLra235Hz,
+    /// Generic 10 mm coin ERM rated 3.0 V.
+    CoinErm3V,
+}
+
+/// Register settings an `ActuatorPreset` stands for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ActuatorSettings {
+    pub motor_type: MotorType,
+    pub rated_voltage_mv: u16,
+    pub overdrive_voltage_mv: u16,
+    /// LRA resonance, used as the open-loop frequency; 0 for ERM.
+    pub resonance_hz: u16,
+    pub drive_time_us: u16,
+}
+
+impl ActuatorPreset {
+    pub const fn settings(self) -> ActuatorSettings {
+        match self {
+            ActuatorPreset::VybronicsVlv101040a => ActuatorSettings {
+                motor_type: MotorType::LRA,
+                rated_voltage_mv: 2000,
+                overdrive_voltage_mv: 2800,
+                resonance_hz: 170,
+                drive_time_us: 2900,
+            },
+            ActuatorPreset::CoinLra235Hz => ActuatorSettings {
+                motor_type: MotorType::LRA,
+                rated_voltage_mv: 2000,
+                overdrive_voltage_mv: 3200,
+                resonance_hz: 235,
+                drive_time_us: 2100,
+            },
+            ActuatorPreset::CoinErm3V => ActuatorSettings {
+                motor_type: MotorType::ERM,
+                rated_voltage_mv: 3000,
+                overdrive_voltage_mv: 3600,
+                resonance_hz: 0,
+                drive_time_us: 4800,
+            },
+        }
+    }
+}
+
+/// What playback methods do when a library sequence is still running (GO set).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ConflictPolicy {
+    /// Stop the running sequence before starting the new playback.
+    #[default]
+    Stop,
+    /// Leave it running and return `Error::Busy`.
+    Busy,
+}
+
+/// Set of STATUS fault conditions, using the STATUS register bit positions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FaultMask(u8);
+
+impl FaultMask {
+    pub const NONE: Self = Self(0x00);
+    pub const OVER_CURRENT: Self = Self(0x01);
+    pub const OVER_TEMP: Self = Self(0x02);
+    pub const DIAG_FAIL: Self = Self(0x08);
+    pub const ALL: Self = Self(0x0B);
+
+    pub const fn from_status(status: u8) -> Self {
+        Self(status & Self::ALL.0)
+    }
+
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub const fn intersection(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl core::ops::BitOr for FaultMask {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/// Decoded STATUS register.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Status {
+    pub device_id: u8,
+    /// DIAG_RESULT was clear after the last diagnostic or calibration run.
+    pub diag_passed: bool,
+    pub over_temp: bool,
+    pub over_current: bool,
+}
+
+impl Status {
+    pub const fn from_register(status: u8) -> Self {
+        Self {
+            device_id: (status >> 5) & 0x07,
+            diag_passed: status & 0x08 == 0,
+            over_temp: status & 0x02 != 0,
+            over_current: status & 0x01 != 0,
+        }
+    }
+}
+
+/// Part family decoded from the STATUS DEVICE_ID field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum DeviceVariant {
+    Drv2605l,
+    Drv2604l,
+    Drv2605,
+    Drv2604,
+    /// A DEVICE_ID this crate does not know, as read.
+    Unknown(u8),
+}
+
+impl DeviceVariant {
+    pub const fn from_device_id(id: u8) -> Self {
+        match id {
+            7 => DeviceVariant::Drv2605l,
+            6 => DeviceVariant::Drv2604l,
+            3 => DeviceVariant::Drv2605,
+            4 => DeviceVariant::Drv2604,
+            other => DeviceVariant::Unknown(other),
+        }
+    }
+
+    /// The DRV2604 parts keep waveforms in RAM and have no ROM effect
+    /// libraries, so `Effect` playback does nothing useful on them.
+    pub const fn has_rom_library(self) -> bool {
+        matches!(self, DeviceVariant::Drv2605l | DeviceVariant::Drv2605)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Diagnostics {
+    /// No diagnostic failure, overtemperature or overcurrent was flagged.
+    pub device_passed: bool,
+    /// Raw DIAG_RESULT bit; set means the actuator check failed.
+    pub diag_result: bool,
+}
+
+/// Auto-calibration outputs, suitable for persisting and restoring with
+/// `apply_calibration` to skip calibration on later boots. A result is only
+/// produced when DIAG_RESULT reported success.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CalibrationResult {
+    pub comp: u8,
+    pub bemf: u8,
+    pub bemf_gain: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct StressReport {
+    pub completed_cycles: u32,
+    /// Fault that aborted the run, if any.
+    pub fault: Option<FaultMask>,
+}
+
+/// ROM waveform library, register 0x03 bits 2:0. The ERM libraries differ in
+/// the actuator they were tuned for (rated/overdrive voltage, rise and brake time).
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Library {
+    /// No waveforms; use with RTP or external input.
     Empty = 0,
+    /// 1.3 V rated, 3 V overdrive, 40–60 ms rise, 20–40 ms brake.
     LibraryA = 1,
+    /// 3 V rated, 3 V overdrive, 40–60 ms rise, 5–15 ms brake.
     LibraryB = 2,
+    /// 3 V rated, 3 V overdrive, 60–80 ms rise, 10–20 ms brake.
     LibraryC = 3,
+    /// 3 V rated, 3 V overdrive, 100–140 ms rise, 15–25 ms brake.
     LibraryD = 4,
+    /// 3 V rated, 3 V overdrive, over 140 ms rise, over 30 ms brake.
     LibraryE = 5,
     LRA = 6,
 }
 
+impl Library {
+    /// Library `init` selects when `Config::library` is not set.
+    pub const fn default_for(motor_type: MotorType) -> Self {
+        match motor_type {
+            MotorType::LRA => Library::LRA,
+            MotorType::ERM => Library::LibraryB,
+        }
+    }
+
+    /// Longest brake phase the library's effects use, from the ranges above.
+    /// Library E and the closed-loop LRA library give no upper bound, so 50 ms
+    /// and 20 ms are assumed.
+    pub const fn max_brake_time(self) -> core::time::Duration {
+        let ms = match self {
+            Library::Empty => 0,
+            Library::LibraryA => 40,
+            Library::LibraryB => 15,
+            Library::LibraryC => 20,
+            Library::LibraryD => 25,
+            Library::LibraryE => 50,
+            Library::LRA => 20,
+        };
+        core::time::Duration::from_millis(ms)
+    }
+
+    /// Actuator type the library's waveforms are tuned for; `None` for `Empty`.
+    pub const fn motor_type(self) -> Option<MotorType> {
+        match self {
+            Library::Empty => None,
+            Library::LRA => Some(MotorType::LRA),
+            _ => Some(MotorType::ERM),
+        }
+    }
+}
+
+/// Whether `effect` plays anything under `library`. Every ROM library holds
+/// effects 1..=123 at the same indices; the empty library holds none.
+pub const fn effect_valid_for_library(effect: u8, library: Library) -> bool {
+    !matches!(library, Library::Empty) && effect >= 1 && effect <= 123
+}
+
 #[repr(u8)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Effect {
     StrongClick100 = 1,
@@ -168,9 +706,919 @@ pub enum Effect {
 }
 
 impl Effect {
-    pub fn as_u8(self) -> u8 {
+    /// Every ROM effect, in index order (`ALL[i]` has index `i + 1`).
+    pub const ALL: [Effect; 123] = [
+        Effect::StrongClick100,
+        Effect::StrongClick60,
+        Effect::StrongClick30,
+        Effect::SharpClick100,
+        Effect::SharpClick60,
+        Effect::SharpClick30,
+        Effect::SoftBump100,
+        Effect::SoftBump60,
+        Effect::SoftBump30,
+        Effect::DoubleClick100,
+        Effect::DoubleClick60,
+        Effect::TripleClick100,
+        Effect::SoftFuzz60,
+        Effect::StrongBuzz100,
+        Effect::Alert750ms,
+        Effect::Alert1000ms,
+        Effect::StrongClick1_100,
+        Effect::StrongClick2_80,
+        Effect::StrongClick3_60,
+        Effect::StrongClick4_30,
+        Effect::MediumClick1_100,
+        Effect::MediumClick2_80,
+        Effect::MediumClick3_60,
+        Effect::SharpTick1_100,
+        Effect::SharpTick2_80,
+        Effect::SharpTick3_60,
+        Effect::ShortDoubleClickStrong1_100,
+        Effect::ShortDoubleClickStrong2_80,
+        Effect::ShortDoubleClickStrong3_60,
+        Effect::ShortDoubleClickStrong4_30,
+        Effect::ShortDoubleClickMedium1_100,
+        Effect::ShortDoubleClickMedium2_80,
+        Effect::ShortDoubleClickMedium3_60,
+        Effect::ShortDoubleSharpTick1_100,
+        Effect::ShortDoubleSharpTick2_80,
+        Effect::ShortDoubleSharpTick3_60,
+        Effect::LongDoubleSharpClickStrong1_100,
+        Effect::LongDoubleSharpClickStrong2_80,
+        Effect::LongDoubleSharpClickStrong3_60,
+        Effect::LongDoubleSharpClickStrong4_30,
+        Effect::LongDoubleSharpClickMedium1_100,
+        Effect::LongDoubleSharpClickMedium2_80,
+        Effect::LongDoubleSharpClickMedium3_60,
+        Effect::LongDoubleSharpTick1_100,
+        Effect::LongDoubleSharpTick2_80,
+        Effect::LongDoubleSharpTick3_60,
+        Effect::Buzz1_100,
+        Effect::Buzz2_80,
+        Effect::Buzz3_60,
+        Effect::Buzz4_40,
+        Effect::Buzz5_20,
+        Effect::PulsingStrong1_100,
+        Effect::PulsingStrong2_60,
+        Effect::PulsingMedium1_100,
+        Effect::PulsingMedium2_60,
+        Effect::PulsingSharp1_100,
+        Effect::PulsingSharp2_60,
+        Effect::TransitionClick1_100,
+        Effect::TransitionClick2_80,
+        Effect::TransitionClick3_60,
+        Effect::TransitionClick4_40,
+        Effect::TransitionClick5_20,
+        Effect::TransitionClick6_10,
+        Effect::TransitionHum1_100,
+        Effect::TransitionHum2_80,
+        Effect::TransitionHum3_60,
+        Effect::TransitionHum4_40,
+        Effect::TransitionHum5_20,
+        Effect::TransitionHum6_10,
+        Effect::TransitionRampDownLongSmooth1_100,
+        Effect::TransitionRampDownLongSmooth2_100,
+        Effect::TransitionRampDownMediumSmooth1_100,
+        Effect::TransitionRampDownMediumSmooth2_100,
+        Effect::TransitionRampDownShortSmooth1_100,
+        Effect::TransitionRampDownShortSmooth2_100,
+        Effect::TransitionRampDownLongSharp1_100,
+        Effect::TransitionRampDownLongSharp2_100,
+        Effect::TransitionRampDownMediumSharp1_100,
+        Effect::TransitionRampDownMediumSharp2_100,
+        Effect::TransitionRampDownShortSharp1_100,
+        Effect::TransitionRampDownShortSharp2_100,
+        Effect::TransitionRampUpLongSmooth1_100,
+        Effect::TransitionRampUpLongSmooth2_100,
+        Effect::TransitionRampUpMediumSmooth1_100,
+        Effect::TransitionRampUpMediumSmooth2_100,
+        Effect::TransitionRampUpShortSmooth1_100,
+        Effect::TransitionRampUpShortSmooth2_100,
+        Effect::TransitionRampUpLongSharp1_100,
+        Effect::TransitionRampUpLongSharp2_100,
+        Effect::TransitionRampUpMediumSharp1_100,
+        Effect::TransitionRampUpMediumSharp2_100,
+        Effect::TransitionRampUpShortSharp1_100,
+        Effect::TransitionRampUpShortSharp2_100,
+        Effect::TransitionRampDownLongSmooth1_50,
+        Effect::TransitionRampDownLongSmooth2_50,
+        Effect::TransitionRampDownMediumSmooth1_50,
+        Effect::TransitionRampDownMediumSmooth2_50,
+        Effect::TransitionRampDownShortSmooth1_50,
+        Effect::TransitionRampDownShortSmooth2_50,
+        Effect::TransitionRampDownLongSharp1_50,
+        Effect::TransitionRampDownLongSharp2_50,
+        Effect::TransitionRampDownMediumSharp1_50,
+        Effect::TransitionRampDownMediumSharp2_50,
+        Effect::TransitionRampDownShortSharp1_50,
+        Effect::TransitionRampDownShortSharp2_50,
+        Effect::TransitionRampUpLongSmooth1_50,
+        Effect::TransitionRampUpLongSmooth2_50,
+        Effect::TransitionRampUpMediumSmooth1_50,
+        Effect::TransitionRampUpMediumSmooth2_50,
+        Effect::TransitionRampUpShortSmooth1_50,
+        Effect::TransitionRampUpShortSmooth2_50,
+        Effect::TransitionRampUpLongSharp1_50,
+        Effect::TransitionRampUpLongSharp2_50,
+        Effect::TransitionRampUpMediumSharp1_50,
+        Effect::TransitionRampUpMediumSharp2_50,
+        Effect::TransitionRampUpShortSharp1_50,
+        Effect::TransitionRampUpShortSharp2_50,
+        Effect::LongBuzz100,
+        Effect::SmoothHum1_50,
+        Effect::SmoothHum2_40,
+        Effect::SmoothHum3_30,
+        Effect::SmoothHum4_20,
+        Effect::SmoothHum5_10,
+    ];
+
+    pub const fn as_u8(self) -> u8 {
         self as u8
     }
+
+    pub const fn category(self) -> EffectCategory {
+        match self as u8 {
+            1..=6 | 17..=26 => EffectCategory::Click,
+            7..=9 => EffectCategory::Bump,
+            10 | 11 | 27..=46 => EffectCategory::DoubleClick,
+            12 => EffectCategory::TripleClick,
+            13 | 14 | 47..=51 | 118 => EffectCategory::Buzz,
+            15 | 16 => EffectCategory::Alert,
+            52..=57 => EffectCategory::Pulse,
+            58..=63 => EffectCategory::TransitionClick,
+            64..=69 => EffectCategory::TransitionHum,
+            70..=81 | 94..=105 => EffectCategory::TransitionRampDown,
+            82..=93 | 106..=117 => EffectCategory::TransitionRampUp,
+            _ => EffectCategory::Hum,
+        }
+    }
+
+    /// Strong click closest to `strength` percent (100, 80, 60 or 30).
+    pub const fn click(strength: u8) -> Effect {
+        nearest_strength(
+            strength,
+            &[
+                (100, Effect::StrongClick1_100),
+                (80, Effect::StrongClick2_80),
+                (60, Effect::StrongClick3_60),
+                (30, Effect::StrongClick4_30),
+            ],
+        )
+    }
+
+    /// Buzz closest to `strength` percent (100, 80, 60, 40 or 20).
+    pub const fn buzz(strength: u8) -> Effect {
+        nearest_strength(
+            strength,
+            &[
+                (100, Effect::Buzz1_100),
+                (80, Effect::Buzz2_80),
+                (60, Effect::Buzz3_60),
+                (40, Effect::Buzz4_40),
+                (20, Effect::Buzz5_20),
+            ],
+        )
+    }
+}
+
+/// Entry of `levels` whose strength is closest to `strength`, preferring the
+/// stronger one on a tie.
+const fn nearest_strength(strength: u8, levels: &[(u8, Effect)]) -> Effect {
+    let mut best = levels[0].1;
+    let mut best_distance = u8::MAX;
+    let mut i = 0;
+    while i < levels.len() {
+        let distance = levels[i].0.abs_diff(strength);
+        if distance < best_distance {
+            best = levels[i].1;
+            best_distance = distance;
+        }
+        i += 1;
+    }
+    best
+}
+
+/// Broad feel of a ROM effect, from the effect list in the datasheet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum EffectCategory {
+    Click,
+    Bump,
+    DoubleClick,
+    TripleClick,
+    Buzz,
+    Alert,
+    Pulse,
+    TransitionClick,
+    TransitionHum,
+    TransitionRampUp,
+    TransitionRampDown,
+    Hum,
+}
+
+impl EffectCategory {
+    /// Effects in this category, in index order.
+    pub fn effects(self) -> impl Iterator<Item = Effect> {
+        Effect::ALL
+            .into_iter()
+            .filter(move |effect| effect.category() == self)
+    }
+}
+
+impl TryFrom<u8> for Effect {
+    type Error = u8;
+
+    /// Fails with the original byte for 0, wait commands and indices above 123.
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1..=123 => Ok(Self::ALL[value as usize - 1]),
+            _ => Err(value),
+        }
+    }
+}
+
+/// Attack/decay smoother mapping an app-provided level (0–255) to an RTP amplitude.
+///
+/// `attack` and `decay` are the fraction (out of 255) of the distance to the new
+/// level covered per update while rising and falling; 255 follows instantly.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct LevelMeter {
+    pub attack: u8,
+    pub decay: u8,
+    value: u8,
+}
+
+impl LevelMeter {
+    pub const fn new(attack: u8, decay: u8) -> Self {
+        Self {
+            attack,
+            decay,
+            value: 0,
+        }
+    }
+
+    pub const fn value(&self) -> u8 {
+        self.value
+    }
+
+    pub fn update(&mut self, level: u8) -> u8 {
+        let coeff = if level > self.value {
+            self.attack
+        } else {
+            self.decay
+        };
+        let diff = level as i32 - self.value as i32;
+        let mut step = diff * coeff as i32 / 255;
+        if step == 0 && coeff > 0 {
+            step = diff.signum();
+        }
+        self.value = (self.value as i32 + step) as u8;
+        self.value
+    }
+}
+
+/// RTP amplitude envelope defined by `(time, amplitude)` keyframes in ascending
+/// time order, linearly interpolated and written every `tick` during playback.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RtpEnvelope<'a> {
+    keyframes: &'a [(core::time::Duration, u8)],
+    tick: core::time::Duration,
+}
+
+impl<'a> RtpEnvelope<'a> {
+    pub const fn new(
+        keyframes: &'a [(core::time::Duration, u8)],
+        tick: core::time::Duration,
+    ) -> Self {
+        Self { keyframes, tick }
+    }
+
+    pub const fn tick(&self) -> core::time::Duration {
+        self.tick
+    }
+
+    pub fn duration(&self) -> core::time::Duration {
+        self.keyframes
+            .last()
+            .map_or(core::time::Duration::ZERO, |&(t, _)| t)
+    }
+
+    pub fn amplitude_at(&self, t: core::time::Duration) -> u8 {
+        let Some(&(first_t, first_a)) = self.keyframes.first() else {
+            return 0;
+        };
+        if t <= first_t {
+            return first_a;
+        }
+
+        for pair in self.keyframes.windows(2) {
+            let (t0, a0) = pair[0];
+            let (t1, a1) = pair[1];
+            if t <= t1 {
+                if t1 <= t0 {
+                    return a1;
+                }
+                let span = (t1 - t0).as_micros() as i64;
+                let offset = (t - t0).as_micros() as i64;
+                return (a0 as i64 + (a1 as i64 - a0 as i64) * offset / span) as u8;
+            }
+        }
+
+        self.keyframes[self.keyframes.len() - 1].1
+    }
+}
+
+/// Fixed-capacity sequence for the eight hardware sequencer slots. Steps beyond
+/// the eighth, or waits outside 10..=1270 ms, mark the sequence invalid and make
+/// `play_sequence` return `Error::InvalidParameter`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct WaveformSequence {
+    steps: [u8; 8],
+    len: u8,
+    invalid: bool,
+}
+
+impl WaveformSequence {
+    pub const fn new() -> Self {
+        Self {
+            steps: [0; 8],
+            len: 0,
+            invalid: false,
+        }
+    }
+
+    pub const fn effect(self, effect: Effect) -> Self {
+        self.push(effect.as_u8())
+    }
+
+    /// Appends a wait; accepts `core::time::Duration` or `embassy_time::Duration`.
+    pub fn wait(self, duration: impl Into<core::time::Duration>) -> Self {
+        let ms = u32::try_from(duration.into().as_millis()).unwrap_or(u32::MAX);
+        match wait_step(ms) {
+            Some(step) => self.push(step),
+            None => self.invalidate(),
+        }
+    }
+
+    pub fn steps(&self) -> &[u8] {
+        &self.steps[..self.len as usize]
+    }
+
+    pub const fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub const fn remaining(&self) -> usize {
+        8 - self.len as usize
+    }
+
+    pub const fn is_valid(&self) -> bool {
+        !self.invalid
+    }
+
+    const fn push(mut self, step: u8) -> Self {
+        if self.len as usize == self.steps.len() {
+            return self.invalidate();
+        }
+        self.steps[self.len as usize] = step;
+        self.len += 1;
+        self
+    }
+
+    const fn invalidate(mut self) -> Self {
+        self.invalid = true;
+        self
+    }
+}
+
+/// One waveform sequencer slot.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WaveformStep {
+    Effect(Effect),
+    /// Wait command, encoded like `wait_step`: 10 ms units, 10..=1270 ms.
+    Wait(core::time::Duration),
+    /// Byte written as-is, e.g. `0` to terminate the sequence.
+    Raw(u8),
+}
+
+impl WaveformStep {
+    /// Sequencer byte for this step; `None` for a wait outside 10..=1270 ms.
+    pub fn to_byte(self) -> Option<u8> {
+        match self {
+            WaveformStep::Effect(effect) => Some(effect.as_u8()),
+            WaveformStep::Wait(duration) => {
+                wait_step(u32::try_from(duration.as_millis()).unwrap_or(u32::MAX))
+            }
+            WaveformStep::Raw(byte) => Some(byte),
+        }
+    }
+}
+
+impl From<Effect> for WaveformStep {
+    fn from(effect: Effect) -> Self {
+        WaveformStep::Effect(effect)
+    }
+}
+
+impl From<core::time::Duration> for WaveformStep {
+    fn from(duration: core::time::Duration) -> Self {
+        WaveformStep::Wait(duration)
+    }
+}
+
+#[cfg(feature = "async")]
+impl From<embassy_time::Duration> for WaveformStep {
+    fn from(duration: embassy_time::Duration) -> Self {
+        WaveformStep::Wait(core::time::Duration::from_micros(duration.as_micros()))
+    }
+}
+
+impl From<u8> for WaveformStep {
+    fn from(byte: u8) -> Self {
+        WaveformStep::Raw(byte)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for WaveformStep {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            WaveformStep::Effect(effect) => defmt::write!(f, "Effect({})", effect),
+            WaveformStep::Wait(duration) => {
+                defmt::write!(f, "Wait({}ms)", duration.as_millis() as u64)
+            }
+            WaveformStep::Raw(byte) => defmt::write!(f, "Raw({=u8:#x})", byte),
+        }
+    }
 }
 
 pub const DRV2605L_ADDR: u8 = 0x5A;
+
+/// Heap-backed waveform sequence of any length. Drivers play it in chunks of
+/// eight sequencer slots, re-triggering GO once each chunk finishes.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DynamicSequence {
+    steps: Vec<u8>,
+}
+
+#[cfg(feature = "alloc")]
+impl DynamicSequence {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn effect(mut self, effect: Effect) -> Self {
+        self.steps.push(effect.as_u8());
+        self
+    }
+
+    /// Appends a wait, split across as many 1270 ms wait slots as needed.
+    pub fn wait(mut self, duration: impl Into<core::time::Duration>) -> Self {
+        let mut remaining = u32::try_from(duration.into().as_millis()).unwrap_or(u32::MAX);
+        while remaining > 1270 {
+            self.steps.push(0xFF);
+            remaining -= 1270;
+        }
+        if let Some(step) = wait_step(remaining) {
+            self.steps.push(step);
+        }
+        self
+    }
+
+    pub fn steps(&self) -> &[u8] {
+        &self.steps
+    }
+
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+/// Raw snapshot of registers 0x00..=0x22, named after `registers`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RegisterDump {
+    pub status: u8,
+    pub mode: u8,
+    pub rtp_input: u8,
+    pub library_selection: u8,
+    pub waveform_sequencer: [u8; 8],
+    pub go: u8,
+    pub overdrive_time_offset: u8,
+    pub sustain_time_offset_pos: u8,
+    pub sustain_time_offset_neg: u8,
+    pub brake_time_offset: u8,
+    pub audio_to_vibe_control: u8,
+    pub audio_to_vibe_min_input: u8,
+    pub audio_to_vibe_max_input: u8,
+    pub audio_to_vibe_min_output: u8,
+    pub audio_to_vibe_max_output: u8,
+    pub rated_voltage: u8,
+    pub overdrive_clamp_voltage: u8,
+    pub auto_calib_comp_result: u8,
+    pub auto_calib_back_emf_result: u8,
+    pub feedback_control: u8,
+    pub control1: u8,
+    pub control2: u8,
+    pub control3: u8,
+    pub control4: u8,
+    pub control5: u8,
+    pub lra_loop_period: u8,
+    pub vbat_voltage_monitor: u8,
+    pub lra_resonance_period: u8,
+}
+
+impl RegisterDump {
+    /// Number of registers covered, starting at STATUS.
+    pub const LEN: usize = registers::LRA_RESONANCE_PERIOD as usize + 1;
+
+    pub const fn from_bytes(raw: &[u8; Self::LEN]) -> Self {
+        Self {
+            status: raw[0],
+            mode: raw[1],
+            rtp_input: raw[2],
+            library_selection: raw[3],
+            waveform_sequencer: [
+                raw[4], raw[5], raw[6], raw[7], raw[8], raw[9], raw[10], raw[11],
+            ],
+            go: raw[12],
+            overdrive_time_offset: raw[13],
+            sustain_time_offset_pos: raw[14],
+            sustain_time_offset_neg: raw[15],
+            brake_time_offset: raw[16],
+            audio_to_vibe_control: raw[17],
+            audio_to_vibe_min_input: raw[18],
+            audio_to_vibe_max_input: raw[19],
+            audio_to_vibe_min_output: raw[20],
+            audio_to_vibe_max_output: raw[21],
+            rated_voltage: raw[22],
+            overdrive_clamp_voltage: raw[23],
+            auto_calib_comp_result: raw[24],
+            auto_calib_back_emf_result: raw[25],
+            feedback_control: raw[26],
+            control1: raw[27],
+            control2: raw[28],
+            control3: raw[29],
+            control4: raw[30],
+            control5: raw[31],
+            lra_loop_period: raw[32],
+            vbat_voltage_monitor: raw[33],
+            lra_resonance_period: raw[34],
+        }
+    }
+}
+
+/// A register write performed by `init`. Bits outside `mask` are preserved with
+/// a read-modify-write; a full mask is a plain write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RegisterWrite {
+    pub reg: u8,
+    pub mask: u8,
+    pub value: u8,
+}
+
+/// The fixed-encoding writes `init` makes for `config`, in order, after the
+/// reset and its settle delay: exit standby, Feedback Control, library. Feedback
+/// Control only has N_ERM_LRA and the configured brake factor and loop gain
+/// touched, so a calibrated BEMF_GAIN survives. The configured voltages and
+/// supply compensation depend on values read back from the chip and are written
+/// after these.
+pub const fn init_writes(config: &Config) -> [RegisterWrite; 3] {
+    let motor_type = match config.motor_type {
+        Some(motor_type) => motor_type,
+        None => MotorType::LRA,
+    };
+    let mut feedback_mask = 0x80;
+    let mut feedback = match motor_type {
+        MotorType::LRA => 0x80,
+        MotorType::ERM => 0x00,
+    };
+    if let Some(factor) = config.brake_factor {
+        feedback_mask |= 0x70;
+        feedback |= (factor as u8) << 4;
+    }
+    if let Some(gain) = config.loop_gain {
+        feedback_mask |= 0x0C;
+        feedback |= (gain as u8) << 2;
+    }
+    let library = match config.library {
+        Some(library) => library,
+        None => Library::default_for(motor_type),
+    };
+    [
+        RegisterWrite {
+            reg: registers::MODE,
+            mask: 0xFF,
+            value: 0x00,
+        },
+        RegisterWrite {
+            reg: registers::FEEDBACK_CONTROL,
+            mask: feedback_mask,
+            value: feedback,
+        },
+        RegisterWrite {
+            reg: registers::LIBRARY_SELECTION,
+            mask: 0xFF,
+            value: library as u8,
+        },
+    ]
+}
+
+/// International Morse code for an ASCII letter or digit, as `.` and `-` symbols.
+pub fn morse_code(c: char) -> Option<&'static str> {
+    let code = match c.to_ascii_uppercase() {
+        'A' => ".-",
+        'B' => "-...",
+        'C' => "-.-.",
+        'D' => "-..",
+        'E' => ".",
+        'F' => "..-.",
+        'G' => "--.",
+        'H' => "....",
+        'I' => "..",
+        'J' => ".---",
+        'K' => "-.-",
+        'L' => ".-..",
+        'M' => "--",
+        'N' => "-.",
+        'O' => "---",
+        'P' => ".--.",
+        'Q' => "--.-",
+        'R' => ".-.",
+        'S' => "...",
+        'T' => "-",
+        'U' => "..-",
+        'V' => "...-",
+        'W' => ".--",
+        'X' => "-..-",
+        'Y' => "-.--",
+        'Z' => "--..",
+        '0' => "-----",
+        '1' => ".----",
+        '2' => "..---",
+        '3' => "...--",
+        '4' => "....-",
+        '5' => ".....",
+        '6' => "-....",
+        '7' => "--...",
+        '8' => "---..",
+        '9' => "----.",
+        _ => return None,
+    };
+    Some(code)
+}
+
+/// Encodes a sequencer wait of `ms` milliseconds, rounded to the nearest 10 ms.
+/// Returns `None` outside the representable 10..=1270 ms range.
+pub const fn wait_step(ms: u32) -> Option<u8> {
+    let units = ms.saturating_add(5) / 10;
+    if units == 0 || units > 0x7F {
+        return None;
+    }
+    Some(0x80 | units as u8)
+}
+
+/// Full scale of the overdrive clamp register.
+pub const OVERDRIVE_FULL_SCALE_MV: u16 = 5600;
+
+/// OD_CLAMP register value for `mv`, truncated to the 21.96 mV step below.
+/// `None` above the 5.6 V full scale.
+pub const fn overdrive_voltage_code(mv: u16) -> Option<u8> {
+    if mv > OVERDRIVE_FULL_SCALE_MV {
+        return None;
+    }
+    Some(((mv as u32 * 255) / OVERDRIVE_FULL_SCALE_MV as u32) as u8)
+}
+
+/// OD_CLAMP register value for `target_mv`, limited to the measured supply `vbat_mv`.
+/// `None` if `target_mv` is above the 5.6 V full scale, like
+/// [`overdrive_voltage_code`].
+pub const fn overdrive_clamp_raw(target_mv: u16, vbat_mv: u16) -> Option<u8> {
+    if target_mv > OVERDRIVE_FULL_SCALE_MV {
+        return None;
+    }
+    let mv = if target_mv < vbat_mv {
+        target_mv
+    } else {
+        vbat_mv
+    };
+    overdrive_voltage_code(mv)
+}
+
+/// RATED_VOLTAGE register value for `mv`, rounded to the nearest step.
+///
+/// ERM closed loop is an average of 21.18 mV per step. LRA closed loop is RMS at
+/// 20.58 mV per step, raised by `1 / sqrt(1 - (4 * t_sample + 300 µs) * f_lra)`
+/// to make up for the back-EMF sampling window, so `sample_time` and `lra_hz`
+/// only matter for LRA. `None` if `mv` is beyond the register's range.
+pub const fn rated_voltage_code(
+    motor_type: MotorType,
+    mv: u16,
+    sample_time: SampleTime,
+    lra_hz: u16,
+) -> Option<u8> {
+    let code = match motor_type {
+        MotorType::ERM => (mv as u64 * 100 + 2118 / 2) / 2118,
+        MotorType::LRA => {
+            let factor = lra_rms_factor_ppm(sample_time, lra_hz);
+            if factor == 0 {
+                return if mv == 0 { Some(0) } else { None };
+            }
+            (mv as u64 * 100 * factor + 1_029_000_000) / 2_058_000_000
+        }
+    };
+    if code > 0xFF {
+        None
+    } else {
+        Some(code as u8)
+    }
+}
+
+/// Millivolts represented by a RATED_VOLTAGE `code`; the inverse of
+/// [`rated_voltage_code`], saturating at `u16::MAX`.
+pub const fn rated_voltage_mv(
+    motor_type: MotorType,
+    code: u8,
+    sample_time: SampleTime,
+    lra_hz: u16,
+) -> u16 {
+    let mv = match motor_type {
+        MotorType::ERM => (code as u64 * 2118 + 50) / 100,
+        MotorType::LRA => {
+            let factor = lra_rms_factor_ppm(sample_time, lra_hz);
+            if factor == 0 {
+                return u16::MAX;
+            }
+            (code as u64 * 20_580_000 + factor / 2) / factor
+        }
+    };
+    if mv > u16::MAX as u64 {
+        u16::MAX
+    } else {
+        mv as u16
+    }
+}
+
+/// OD_CLAMP register value that drives `mv` in open loop, rounded to the
+/// nearest step. Open loop has its own scale: an ERM average of 21.32 mV per
+/// step, and for an LRA an RMS of 21.32 mV per step times
+/// `sqrt(1 - f_ol_lra * 800 µs)`, where `ol_lra_hz` is the open-loop drive
+/// frequency. `None` if `mv` is beyond the register's range.
+pub const fn open_loop_drive_code(motor_type: MotorType, mv: u16, ol_lra_hz: u16) -> Option<u8> {
+    let code = match motor_type {
+        MotorType::ERM => (mv as u64 * 100 + 2132 / 2) / 2132,
+        MotorType::LRA => {
+            // Hz times µs is parts per million.
+            let window = 800 * ol_lra_hz as u64;
+            if window >= 1_000_000 {
+                return if mv == 0 { Some(0) } else { None };
+            }
+            let factor = isqrt((1_000_000 - window) * 1_000_000);
+            let divisor = 2132 * factor;
+            (mv as u64 * 100_000_000 + divisor / 2) / divisor
+        }
+    };
+    if code > 0xFF {
+        None
+    } else {
+        Some(code as u8)
+    }
+}
+
+/// Open-loop drive voltage in millivolts for OD_CLAMP `code`, the inverse of
+/// `open_loop_drive_code`.
+pub const fn open_loop_drive_mv(motor_type: MotorType, code: u8, ol_lra_hz: u16) -> u16 {
+    let mv = match motor_type {
+        MotorType::ERM => (code as u64 * 2132 + 50) / 100,
+        MotorType::LRA => {
+            let window = 800 * ol_lra_hz as u64;
+            if window >= 1_000_000 {
+                return 0;
+            }
+            let factor = isqrt((1_000_000 - window) * 1_000_000);
+            (code as u64 * 2132 * factor + 50_000_000) / 100_000_000
+        }
+    };
+    if mv > u16::MAX as u64 {
+        u16::MAX
+    } else {
+        mv as u16
+    }
+}
+
+/// Control1 DRIVE_TIME `code` in microseconds: 500 µs plus 100 µs per step
+/// for an LRA, 1 ms plus 200 µs per step for an ERM.
+pub const fn drive_time_us(motor_type: MotorType, code: u8) -> u16 {
+    let (base, step) = drive_time_scale(motor_type);
+    base + (code & 0x1F) as u16 * step
+}
+
+/// `(base, step)` of the DRIVE_TIME field in microseconds.
+pub(crate) const fn drive_time_scale(motor_type: MotorType) -> (u16, u16) {
+    match motor_type {
+        MotorType::LRA => (500, 100),
+        MotorType::ERM => (1000, 200),
+    }
+}
+
+/// `sqrt(1 - (4 * t_sample + 300 µs) * f)` in parts per million, or 0 when the
+/// sampling window covers the whole period.
+const fn lra_rms_factor_ppm(sample_time: SampleTime, lra_hz: u16) -> u64 {
+    // µs times Hz is already parts per million.
+    let window = (4 * sample_time.micros() + 300) as u64 * lra_hz as u64;
+    if window >= 1_000_000 {
+        return 0;
+    }
+    isqrt((1_000_000 - window) * 1_000_000)
+}
+
+const fn isqrt(n: u64) -> u64 {
+    if n < 2 {
+        return n;
+    }
+    let mut x = n;
+    let mut y = x.div_ceil(2);
+    while y < x {
+        x = y;
+        y = (x + n / x) / 2;
+    }
+    x
+}
+
+/// Packs effects, each followed by its gap in milliseconds (0 for none), into
+/// sequencer slots. `None` if a gap is unrepresentable or the slots run out.
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub(crate) fn encode_timed_sequence(
+    pattern: impl IntoIterator<Item = (Effect, u32)>,
+) -> Option<([u8; 8], usize)> {
+    let mut steps = [0u8; 8];
+    let mut len = 0;
+    for (effect, gap_ms) in pattern {
+        let wait = match gap_ms {
+            0 => None,
+            ms => Some(wait_step(ms)?),
+        };
+        for step in core::iter::once(effect.as_u8()).chain(wait) {
+            *steps.get_mut(len)? = step;
+            len += 1;
+        }
+    }
+    Some((steps, len))
+}
+
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub(crate) fn voltage_code_to_mv(code: u8) -> u16 {
+    ((code as u32 * 5600) / 255) as u16
+}
+
+/// LRA period registers count in units of 98.46 µs.
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub(crate) fn lra_period_to_hz(code: u8) -> Option<u16> {
+    if code == 0 {
+        return None;
+    }
+    let divisor = code as u32 * 9846;
+    Some(((100_000_000 + divisor / 2) / divisor) as u16)
+}
+
+/// Value at step `step` of `steps` on a straight line from `from` to `to`.
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub(crate) fn ramp_value(from: u8, to: u8, step: u16, steps: u16) -> u8 {
+    let span = to as i32 - from as i32;
+    (from as i32 + span * step as i32 / steps as i32) as u8
+}
+
+/// Period register code in microseconds, rounded.
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub(crate) fn lra_period_to_us(code: u8) -> u16 {
+    ((code as u32 * 9846 + 50) / 100) as u16
+}
+
+/// Nearest 7-bit OL_LRA_PERIOD code for `hz`.
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub(crate) fn hz_to_lra_period(hz: u16) -> u8 {
+    let divisor = hz as u32 * 9846;
+    let code = (100_000_000 + divisor / 2) / divisor;
+    code.clamp(1, 0x7F) as u8
+}
+
+/// Nearest 7-bit OL_LRA_PERIOD code for `period_us`.
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub(crate) fn us_to_lra_period(period_us: u16) -> u8 {
+    let code = (period_us as u32 * 100 + 9846 / 2) / 9846;
+    code.clamp(1, 0x7F) as u8
+}
@@ -1,6 +1,10 @@
 #![no_std]
 #![allow(async_fn_in_trait)]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+pub mod audio;
 pub mod common;
 pub mod registers;
 
@@ -10,17 +14,44 @@ pub mod blocking;
 #[cfg(feature = "async")]
 pub mod async_i2c;
 
+pub use audio::{AudioFilter, AudioPeakTime};
+
 // Re-export common types at crate root
-pub use common::{Effect, Error, Library, Mode, MotorType, DRV2605L_ADDR};
+pub use common::{
+    ActuatorPreset, ActuatorSettings, AutoCalTime, BlankingTime, BrakeFactor, CalibrationResult,
+    Config, ConflictPolicy, DeviceVariant, Diagnostics, DriveState, Effect, EffectCategory, Error,
+    FaultMask, IdissTime, InputMode, LevelMeter, Library, LoopGain, LoopMode, Mode, MotorType,
+    RegisterDump, RegisterWrite, RtpEnvelope, RtpFormat, SampleTime, Status, StressReport,
+    TriggerEdge, WaveformSequence, WaveformStep, ZcDetTime, DRV2605L_ADDR,
+};
+
+#[cfg(feature = "alloc")]
+pub use common::DynamicSequence;
 
 // Re-export the appropriate driver based on features
 #[cfg(all(feature = "blocking", not(feature = "async")))]
 pub use blocking::Drv2605l;
 
-#[cfg(feature = "async")]
+#[cfg(all(feature = "async", not(feature = "blocking")))]
 pub use async_i2c::Drv2605l;
 
+#[cfg(feature = "async")]
+pub use async_i2c::{AutoStandby, PlayGuard, StandbyGuard};
+
 // If both features are enabled, require explicit module usage
+/// Both drivers under distinct names. With `async` and `blocking` both enabled
+/// there is no root `Drv2605l`, so pick one here:
+///
+/// ```
+/// use embassy_drv2605l::prelude::{AsyncDrv2605l, BlockingDrv2605l};
+/// use embedded_hal_mock::eh1::i2c::Mock;
+///
+/// let haptic = AsyncDrv2605l::new(Mock::new(&[]));
+/// let status_led = BlockingDrv2605l::new(Mock::new(&[]));
+///
+/// haptic.release().done();
+/// status_led.release().done();
+/// ```
 #[cfg(all(feature = "blocking", feature = "async"))]
 pub mod prelude {
     pub use crate::async_i2c::Drv2605l as AsyncDrv2605l;
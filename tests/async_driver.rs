@@ -0,0 +1,455 @@
+#![cfg(feature = "async")]
+
+use embassy_drv2605l::async_i2c::Drv2605l;
+use embassy_drv2605l::{
+    CalibrationResult, Config, DeviceVariant, Error, InputMode, Library, Mode, MotorType,
+    DRV2605L_ADDR,
+};
+use embassy_time::Duration;
+use embedded_hal_async::i2c::{ErrorKind, NoAcknowledgeSource};
+use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+
+fn write(bytes: &[u8]) -> Transaction {
+    Transaction::write(DRV2605L_ADDR, bytes.to_vec())
+}
+
+fn read(reg: u8, value: u8) -> Transaction {
+    Transaction::write_read(DRV2605L_ADDR, vec![reg], vec![value])
+}
+
+#[tokio::test]
+async fn init_programs_lra_defaults() {
+    let expectations = [
+        write(&[0x01, 0x80]),
+        write(&[0x01, 0x00]),
+        read(0x1A, 0x36),
+        write(&[0x1A, 0xB6]),
+        write(&[0x03, 0x06]),
+    ];
+    let mut driver = Drv2605l::new(Mock::new(&expectations));
+    driver.init().await.unwrap();
+    driver.release().done();
+}
+
+#[tokio::test]
+async fn init_programs_erm_defaults() {
+    let expectations = [
+        write(&[0x01, 0x80]),
+        write(&[0x01, 0x00]),
+        read(0x1A, 0xB6),
+        write(&[0x1A, 0x36]),
+        write(&[0x03, 0x02]),
+    ];
+    let config = Config::new().motor_type(MotorType::ERM);
+    let mut driver = Drv2605l::new_with_config(Mock::new(&expectations), config);
+    driver.init().await.unwrap();
+    driver.release().done();
+}
+
+#[tokio::test]
+async fn detect_and_recover_accepts_configured_library() {
+    let expectations = [
+        write(&[0x01, 0x80]),
+        write(&[0x01, 0x00]),
+        read(0x1A, 0xB6),
+        write(&[0x1A, 0x36]),
+        write(&[0x03, 0x01]),
+        read(0x1A, 0x36),
+        read(0x03, 0x01),
+        write(&[0x03, 0x03]),
+        read(0x1A, 0x36),
+        read(0x03, 0x03),
+    ];
+    let config = Config::new()
+        .motor_type(MotorType::ERM)
+        .library(Library::LibraryA);
+    let mut driver = Drv2605l::new_with_config(Mock::new(&expectations), config);
+    driver.init().await.unwrap();
+    assert!(!driver.detect_and_recover().await.unwrap());
+    driver.set_library(Library::LibraryC).await.unwrap();
+    assert!(!driver.detect_and_recover().await.unwrap());
+    driver.release().done();
+}
+
+#[tokio::test]
+async fn supply_compensation_clamps_on_the_rated_scale() {
+    let expectations = [
+        write(&[0x01, 0x80]),
+        write(&[0x01, 0x00]),
+        read(0x1A, 0xB6),
+        write(&[0x1A, 0x36]),
+        write(&[0x03, 0x02]),
+        read(0x21, 0x80),
+        read(0x1D, 0xA0),
+        write(&[0x1D, 0xA0]),
+        read(0x16, 0xFF),
+        // 128 * 5600 / 255 = 2810 mV, which is 133 at 21.18 mV/step
+        write(&[0x16, 133]),
+    ];
+    let config = Config::new()
+        .motor_type(MotorType::ERM)
+        .auto_supply_compensation(true);
+    let mut driver = Drv2605l::new_with_config(Mock::new(&expectations), config);
+    driver.init().await.unwrap();
+    driver.release().done();
+}
+
+#[tokio::test]
+async fn play_waveform_loads_slot_and_sets_go() {
+    let expectations = [
+        read(0x0C, 0x00),
+        read(0x01, 0x00),
+        write(&[0x01, 0x00]),
+        write(&[0x04, 47, 0, 0, 0, 0, 0, 0, 0]),
+        write(&[0x0C, 0x01]),
+    ];
+    let mut driver = Drv2605l::new(Mock::new(&expectations));
+    driver.play_waveform(47).await.unwrap();
+    driver.release().done();
+}
+
+#[tokio::test]
+async fn play_waveform_rejects_end_marker() {
+    let mut driver = Drv2605l::new(Mock::new(&[]));
+    assert!(matches!(
+        driver.play_waveform(0).await,
+        Err(Error::InvalidParameter)
+    ));
+    driver.release().done();
+}
+
+#[tokio::test]
+async fn set_motor_type_preserves_feedback_bits() {
+    let expectations = [
+        Transaction::write_read(DRV2605L_ADDR, vec![0x1A], vec![0x36]),
+        Transaction::write(DRV2605L_ADDR, vec![0x1A, 0xB6]),
+        Transaction::write(DRV2605L_ADDR, vec![0x03, 0x06]),
+    ];
+    let mut i2c = Mock::new(&expectations);
+
+    let mut driver = Drv2605l::new(i2c.clone());
+    driver.set_motor_type(MotorType::LRA).await.unwrap();
+
+    i2c.done();
+}
+
+#[tokio::test]
+async fn set_input_mode_flips_bidir_bit() {
+    let expectations = [
+        Transaction::write_read(DRV2605L_ADDR, vec![0x1C], vec![0xF5]),
+        Transaction::write(DRV2605L_ADDR, vec![0x1C, 0x75]),
+        Transaction::write_read(DRV2605L_ADDR, vec![0x1C], vec![0x75]),
+        Transaction::write(DRV2605L_ADDR, vec![0x1C, 0xF5]),
+    ];
+    let mut i2c = Mock::new(&expectations);
+
+    let mut driver = Drv2605l::new(i2c.clone());
+    driver
+        .set_input_mode(InputMode::Unidirectional)
+        .await
+        .unwrap();
+    driver
+        .set_input_mode(InputMode::Bidirectional)
+        .await
+        .unwrap();
+
+    i2c.done();
+}
+
+#[tokio::test]
+async fn auto_calibrate_reads_results() {
+    let expectations = [
+        read(0x01, 0x00),
+        write(&[0x01, 0x07]),
+        write(&[0x0C, 0x01]),
+        read(0x0C, 0x01),
+        read(0x0C, 0x00),
+        read(0x00, 0xE0),
+        read(0x18, 0x0C),
+        read(0x19, 0x6C),
+        read(0x1A, 0xB6),
+    ];
+    let mut driver = Drv2605l::new(Mock::new(&expectations));
+    let result = driver.auto_calibrate().await.unwrap();
+    assert_eq!(
+        result,
+        CalibrationResult {
+            comp: 0x0C,
+            bemf: 0x6C,
+            bemf_gain: 0x02,
+        }
+    );
+    driver.release().done();
+}
+
+#[tokio::test]
+async fn auto_calibrate_reports_diag_failure() {
+    let expectations = [
+        read(0x01, 0x00),
+        write(&[0x01, 0x07]),
+        write(&[0x0C, 0x01]),
+        read(0x0C, 0x00),
+        read(0x00, 0xE8),
+    ];
+    let mut driver = Drv2605l::new(Mock::new(&expectations));
+    assert!(matches!(
+        driver.auto_calibrate().await,
+        Err(Error::CalibrationFailed)
+    ));
+    driver.release().done();
+}
+
+#[tokio::test]
+async fn dropped_play_guard_stops_before_next_access() {
+    let expectations = [write(&[0x0C, 0x01]), write(&[0x0C, 0x00]), read(0x0C, 0x00)];
+    let mut driver = Drv2605l::new(Mock::new(&expectations));
+    drop(driver.trigger_guarded().await.unwrap());
+    assert!(!driver.is_playing().await.unwrap());
+    driver.release().done();
+}
+
+#[tokio::test]
+async fn set_overdrive_voltage_rejects_overflow() {
+    let expectations = [write(&[0x17, 0xFF])];
+    let mut driver = Drv2605l::new(Mock::new(&expectations));
+    driver.set_overdrive_voltage(5600).await.unwrap();
+    assert!(matches!(
+        driver.set_overdrive_voltage(5601).await,
+        Err(Error::InvalidParameter)
+    ));
+    driver.release().done();
+}
+
+#[tokio::test]
+async fn standby_guard_sleeps_explicitly_or_after_drop() {
+    let expectations = [
+        write(&[0x01, 0x00]),
+        write(&[0x01, 0x40]),
+        write(&[0x01, 0x00]),
+        write(&[0x01, 0x40]),
+        read(0x00, 0xE0),
+    ];
+    let mut driver = Drv2605l::new(Mock::new(&expectations));
+    driver.wake().await.unwrap().sleep().await.unwrap();
+    drop(driver.wake().await.unwrap());
+    driver.read_status().await.unwrap();
+    driver.release().done();
+}
+
+#[tokio::test]
+async fn address_nack_is_reported() {
+    let expectations =
+        [read(0x00, 0x00).with_error(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address))];
+    let mut driver = Drv2605l::new(Mock::new(&expectations));
+    let err = driver.probe().await.unwrap_err();
+    assert!(err.is_nack());
+    assert!(err.is_address_nack());
+    driver.release().done();
+}
+
+#[tokio::test]
+async fn retry_policy_retries_failed_writes() {
+    let nack = ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data);
+    let expectations = [
+        write(&[0x0C, 0x00]).with_error(nack),
+        write(&[0x0C, 0x00]),
+        write(&[0x0C, 0x00]).with_error(nack),
+    ];
+    let mut driver = Drv2605l::new(Mock::new(&expectations));
+    driver.set_retry_policy(1, Duration::from_millis(1));
+    driver.stop().await.unwrap();
+    driver.set_retry_policy(0, Duration::from_millis(1));
+    assert!(driver.stop().await.unwrap_err().is_nack());
+    driver.release().done();
+}
+
+#[tokio::test]
+async fn identify_decodes_device_id() {
+    let expectations = [read(0x00, 0xE0), read(0x00, 0xC0)];
+    let mut driver = Drv2605l::new(Mock::new(&expectations));
+    assert_eq!(driver.identify().await.unwrap(), DeviceVariant::Drv2605l);
+    let variant = driver.identify().await.unwrap();
+    assert_eq!(variant, DeviceVariant::Drv2604l);
+    assert!(!variant.has_rom_library());
+    driver.release().done();
+}
+
+#[tokio::test]
+async fn get_vbat_mv_scales_to_full_scale() {
+    let expectations = [read(0x21, 0xFF), read(0x21, 0x00)];
+    let mut driver = Drv2605l::new(Mock::new(&expectations));
+    assert_eq!(driver.get_vbat_mv().await.unwrap(), 5600);
+    assert_eq!(driver.get_vbat_mv().await.unwrap(), 0);
+    driver.release().done();
+}
+
+#[tokio::test]
+async fn set_mode_stops_before_leaving_rtp() {
+    let expectations = [
+        read(0x01, 0x05),
+        write(&[0x0C, 0x00]),
+        write(&[0x01, 0x00]),
+        read(0x01, 0x00),
+        write(&[0x01, 0x05]),
+    ];
+    let mut driver = Drv2605l::new(Mock::new(&expectations));
+    driver.set_mode(Mode::InternalTrigger).await.unwrap();
+    driver.set_mode(Mode::RealTimePlayback).await.unwrap();
+    driver.release().done();
+}
+
+#[tokio::test]
+async fn set_waveform_rejects_out_of_range_wait() {
+    let expectations = [write(&[0x05, 0xFF])];
+    let mut driver = Drv2605l::new(Mock::new(&expectations));
+    assert!(matches!(
+        driver.set_waveform(1, Duration::from_millis(1280)).await,
+        Err(Error::InvalidParameter)
+    ));
+    driver
+        .set_waveform(1, Duration::from_millis(1270))
+        .await
+        .unwrap();
+    driver.release().done();
+}
+
+#[tokio::test]
+async fn get_motor_type_leaves_configured_type_for_recovery() {
+    let expectations = [
+        read(0x1A, 0x36),
+        read(0x1A, 0x36),
+        read(0x03, 0x06),
+        write(&[0x01, 0x80]),
+        write(&[0x01, 0x00]),
+        read(0x1A, 0x36),
+        write(&[0x1A, 0xB6]),
+        write(&[0x03, 0x06]),
+    ];
+    let mut driver = Drv2605l::new(Mock::new(&expectations));
+    assert_eq!(driver.get_motor_type().await.unwrap(), MotorType::ERM);
+    assert!(driver.detect_and_recover().await.unwrap());
+    driver.release().done();
+}
+
+#[tokio::test]
+async fn detect_and_recover_restores_runtime_voltages() {
+    let expectations = [
+        write(&[0x16, 142]),
+        write(&[0x17, 136]),
+        read(0x1A, 0x36),
+        read(0x03, 0x00),
+        write(&[0x01, 0x80]),
+        write(&[0x01, 0x00]),
+        read(0x1A, 0x36),
+        write(&[0x1A, 0x36]),
+        write(&[0x03, 0x02]),
+        write(&[0x16, 142]),
+        write(&[0x17, 136]),
+    ];
+    let config = Config::new().motor_type(MotorType::ERM);
+    let mut driver = Drv2605l::new_with_config(Mock::new(&expectations), config);
+    driver.set_rated_voltage(3000).await.unwrap();
+    driver.set_overdrive_voltage(3000).await.unwrap();
+    assert!(driver.detect_and_recover().await.unwrap());
+    driver.release().done();
+}
+
+#[tokio::test]
+async fn detect_and_recover_checks_rated_voltage_and_restores_calibration() {
+    let cal = CalibrationResult {
+        comp: 0x0D,
+        bemf: 0x6C,
+        bemf_gain: 2,
+    };
+    let expectations = [
+        write(&[0x16, 142]),
+        write(&[0x18, 0x0D]),
+        write(&[0x19, 0x6C]),
+        read(0x1A, 0x34),
+        write(&[0x1A, 0x36]),
+        // Feedback and library happen to match the reset values
+        read(0x1A, 0x36),
+        read(0x03, 0x02),
+        read(0x16, 0x3E),
+        write(&[0x01, 0x80]),
+        write(&[0x01, 0x00]),
+        read(0x1A, 0x36),
+        write(&[0x1A, 0x36]),
+        write(&[0x03, 0x02]),
+        write(&[0x16, 142]),
+        write(&[0x18, 0x0D]),
+        write(&[0x19, 0x6C]),
+        read(0x1A, 0x34),
+        write(&[0x1A, 0x36]),
+    ];
+    let config = Config::new().motor_type(MotorType::ERM);
+    let mut driver = Drv2605l::new_with_config(Mock::new(&expectations), config);
+    driver.set_rated_voltage(3000).await.unwrap();
+    driver.apply_calibration(&cal).await.unwrap();
+    assert!(driver.detect_and_recover().await.unwrap());
+    driver.release().done();
+}
+
+#[tokio::test]
+async fn input_mode_entry_points_honor_config_lock() {
+    let mut driver = Drv2605l::new(Mock::new(&[]));
+    driver.lock_config();
+    assert!(matches!(
+        driver.enter_pwm_mode().await,
+        Err(Error::ConfigLocked)
+    ));
+    assert!(matches!(
+        driver.enter_analog_mode().await,
+        Err(Error::ConfigLocked)
+    ));
+    assert!(matches!(
+        driver.enter_audio_to_vibe().await,
+        Err(Error::ConfigLocked)
+    ));
+    driver.release().done();
+}
+
+#[tokio::test]
+async fn read_drive_state_uses_open_loop_scale_and_microseconds() {
+    let expectations = [
+        read(0x16, 0x5C),
+        read(0x17, 0x70),
+        read(0x1B, 0x13),
+        read(0x1D, 0x20),
+    ];
+    let config = Config::new().motor_type(MotorType::ERM);
+    let mut driver = Drv2605l::new_with_config(Mock::new(&expectations), config);
+    let state = driver.read_drive_state().await.unwrap();
+    assert_eq!(state.rated_voltage_mv, 1949);
+    assert_eq!(state.overdrive_voltage_mv, 2388);
+    assert_eq!(state.drive_time_us, 4800);
+    assert_eq!(state.resonance_hz, None);
+    driver.release().done();
+}
+
+#[tokio::test]
+async fn measure_startup_time_ignores_stale_period_until_reads_agree() {
+    let expectations = [
+        read(0x22, 0x28),
+        read(0x0C, 0x00),
+        read(0x01, 0x00),
+        write(&[0x01, 0x00]),
+        write(&[0x04, 47, 0, 0, 0, 0, 0, 0, 0]),
+        write(&[0x0C, 0x01]),
+        // Left over from the last playback, so it doesn't count.
+        read(0x0C, 0x01),
+        read(0x22, 0x28),
+        read(0x0C, 0x01),
+        read(0x22, 0x1E),
+        read(0x0C, 0x01),
+        read(0x22, 0x21),
+        read(0x0C, 0x01),
+        read(0x22, 0x22),
+        read(0x0C, 0x01),
+        read(0x22, 0x21),
+    ];
+    let mut driver = Drv2605l::new(Mock::new(&expectations));
+    let startup = driver.measure_startup_time(47).await.unwrap();
+    assert!(startup < Duration::from_millis(100));
+    driver.release().done();
+}
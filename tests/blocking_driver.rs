@@ -0,0 +1,95 @@
+#![cfg(feature = "blocking")]
+
+use core::time::Duration;
+use embassy_drv2605l::blocking::Drv2605l;
+use embassy_drv2605l::{Error, InputMode, MotorType, DRV2605L_ADDR};
+use embedded_hal_mock::eh1::delay::NoopDelay;
+use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+
+fn write(bytes: &[u8]) -> Transaction {
+    Transaction::write(DRV2605L_ADDR, bytes.to_vec())
+}
+
+fn read(reg: u8, value: u8) -> Transaction {
+    Transaction::write_read(DRV2605L_ADDR, vec![reg], vec![value])
+}
+
+#[test]
+fn init_programs_lra_defaults() {
+    let expectations = [
+        write(&[0x01, 0x80]),
+        write(&[0x01, 0x00]),
+        read(0x1A, 0x36),
+        write(&[0x1A, 0xB6]),
+        write(&[0x03, 0x06]),
+    ];
+    let mut driver = Drv2605l::new(Mock::new(&expectations));
+    driver.init_with_delay(&mut NoopDelay::new()).unwrap();
+    driver.release().done();
+}
+
+#[test]
+fn play_waveform_loads_slot_and_sets_go() {
+    let expectations = [
+        read(0x0C, 0x00),
+        read(0x01, 0x00),
+        write(&[0x01, 0x00]),
+        write(&[0x04, 47, 0, 0, 0, 0, 0, 0, 0]),
+        write(&[0x0C, 0x01]),
+    ];
+    let mut driver = Drv2605l::new(Mock::new(&expectations));
+    driver.play_waveform(47).unwrap();
+    driver.release().done();
+}
+
+#[test]
+fn set_motor_type_preserves_feedback_bits() {
+    let expectations = [
+        Transaction::write_read(DRV2605L_ADDR, vec![0x1A], vec![0x36]),
+        Transaction::write(DRV2605L_ADDR, vec![0x1A, 0xB6]),
+        Transaction::write(DRV2605L_ADDR, vec![0x03, 0x06]),
+    ];
+    let mut i2c = Mock::new(&expectations);
+
+    let mut driver = Drv2605l::new(i2c.clone());
+    driver.set_motor_type(MotorType::LRA).unwrap();
+
+    i2c.done();
+}
+
+#[test]
+fn set_input_mode_flips_bidir_bit() {
+    let expectations = [
+        Transaction::write_read(DRV2605L_ADDR, vec![0x1C], vec![0xF5]),
+        Transaction::write(DRV2605L_ADDR, vec![0x1C, 0x75]),
+        Transaction::write_read(DRV2605L_ADDR, vec![0x1C], vec![0x75]),
+        Transaction::write(DRV2605L_ADDR, vec![0x1C, 0xF5]),
+    ];
+    let mut i2c = Mock::new(&expectations);
+
+    let mut driver = Drv2605l::new(i2c.clone());
+    driver.set_input_mode(InputMode::Unidirectional).unwrap();
+    driver.set_input_mode(InputMode::Bidirectional).unwrap();
+
+    i2c.done();
+}
+
+#[test]
+fn auto_calibrate_times_out_while_go_stays_set() {
+    let expectations = [
+        read(0x01, 0x00),
+        write(&[0x01, 0x07]),
+        write(&[0x0C, 0x01]),
+        read(0x0C, 0x01),
+        read(0x0C, 0x01),
+        read(0x0C, 0x01),
+    ];
+    let mut driver = Drv2605l::new(Mock::new(&expectations));
+    let result = driver.auto_calibrate_with_timeout(
+        Duration::from_millis(20),
+        Duration::from_millis(10),
+        &mut NoopDelay::new(),
+    );
+    assert!(matches!(result, Err(Error::Timeout)));
+    driver.release().done();
+}
@@ -0,0 +1,16 @@
+use core::time::Duration;
+use embassy_drv2605l::RtpEnvelope;
+
+#[test]
+fn envelope_interpolates_between_keyframes() {
+    let keyframes = [
+        (Duration::ZERO, 0),
+        (Duration::from_millis(100), 200),
+        (Duration::from_millis(150), 100),
+    ];
+    let envelope = RtpEnvelope::new(&keyframes, Duration::from_millis(10));
+    assert_eq!(envelope.duration(), Duration::from_millis(150));
+    assert_eq!(envelope.amplitude_at(Duration::from_millis(50)), 100);
+    assert_eq!(envelope.amplitude_at(Duration::from_millis(125)), 150);
+    assert_eq!(envelope.amplitude_at(Duration::from_secs(1)), 100);
+}
@@ -0,0 +1,13 @@
+use embassy_drv2605l::LevelMeter;
+
+#[test]
+fn full_scale_coefficients_follow_without_overflow() {
+    let mut meter = LevelMeter::new(255, 255);
+    assert_eq!(meter.update(255), 255);
+    assert_eq!(meter.update(0), 0);
+
+    let mut meter = LevelMeter::new(200, 200);
+    let rising = meter.update(200);
+    assert!(rising > 150 && rising < 200);
+    assert!(meter.update(0) < rising);
+}
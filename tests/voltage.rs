@@ -0,0 +1,114 @@
+use embassy_drv2605l::common::{
+    open_loop_drive_code, overdrive_clamp_raw, overdrive_voltage_code, rated_voltage_code,
+    rated_voltage_mv,
+};
+use embassy_drv2605l::{ActuatorPreset, MotorType, SampleTime};
+
+#[test]
+fn erm_rated_voltage_is_average_scale() {
+    // 3 V / 21.18 mV = 141.6
+    assert_eq!(
+        rated_voltage_code(MotorType::ERM, 3000, SampleTime::Us300, 0),
+        Some(142)
+    );
+    assert_eq!(
+        rated_voltage_mv(MotorType::ERM, 142, SampleTime::Us300, 0),
+        3008
+    );
+}
+
+#[test]
+fn lra_rated_voltage_accounts_for_sample_time() {
+    // 2 Vrms at 175 Hz: 2 / 20.58 mV * sqrt(1 - (4 * 300 µs + 300 µs) * 175) = 83.5
+    assert_eq!(
+        rated_voltage_code(MotorType::LRA, 2000, SampleTime::Us300, 175),
+        Some(83)
+    );
+    // A shorter sampling window needs a higher code for the same RMS voltage.
+    assert_eq!(
+        rated_voltage_code(MotorType::LRA, 2000, SampleTime::Us150, 175),
+        Some(89)
+    );
+    assert_eq!(
+        rated_voltage_mv(MotorType::LRA, 83, SampleTime::Us300, 175),
+        1989
+    );
+}
+
+#[test]
+fn rated_voltage_out_of_range() {
+    assert_eq!(
+        rated_voltage_code(MotorType::ERM, 6000, SampleTime::Us300, 0),
+        None
+    );
+    // The sampling window covers the whole period at 1 kHz.
+    assert_eq!(
+        rated_voltage_code(MotorType::LRA, 2000, SampleTime::Us300, 1000),
+        None
+    );
+}
+
+#[test]
+fn overdrive_voltage_boundaries() {
+    assert_eq!(overdrive_voltage_code(0), Some(0));
+    assert_eq!(overdrive_voltage_code(5600), Some(255));
+    assert_eq!(overdrive_voltage_code(5601), None);
+}
+
+#[test]
+fn erm_rated_voltage_boundaries() {
+    assert_eq!(
+        rated_voltage_code(MotorType::ERM, 0, SampleTime::Us300, 0),
+        Some(0)
+    );
+    // 255 steps of 21.18 mV, plus half a step of rounding.
+    assert_eq!(
+        rated_voltage_code(MotorType::ERM, 5411, SampleTime::Us300, 0),
+        Some(255)
+    );
+    assert_eq!(
+        rated_voltage_code(MotorType::ERM, 5412, SampleTime::Us300, 0),
+        None
+    );
+}
+
+#[test]
+fn open_loop_drive_scale() {
+    // 3 V / 21.32 mV = 140.7
+    assert_eq!(open_loop_drive_code(MotorType::ERM, 3000, 0), Some(141));
+    // 2 Vrms at 175 Hz: 2 / 21.32 mV / sqrt(1 - 175 * 800 µs) = 101.2
+    assert_eq!(open_loop_drive_code(MotorType::LRA, 2000, 175), Some(101));
+    assert_eq!(open_loop_drive_code(MotorType::ERM, 5600, 0), None);
+}
+
+#[test]
+fn actuator_presets_fit_the_registers() {
+    for preset in [
+        ActuatorPreset::VybronicsVlv101040a,
+        ActuatorPreset::CoinLra235Hz,
+        ActuatorPreset::CoinErm3V,
+    ] {
+        let settings = preset.settings();
+        let rated = rated_voltage_code(
+            settings.motor_type,
+            settings.rated_voltage_mv,
+            SampleTime::Us300,
+            settings.resonance_hz,
+        );
+        assert!(rated.is_some(), "{preset:?}");
+        assert!(overdrive_voltage_code(settings.overdrive_voltage_mv).is_some());
+    }
+}
+
+#[test]
+fn supply_limited_overdrive_rejects_over_full_scale() {
+    assert_eq!(
+        overdrive_clamp_raw(3000, 5600),
+        overdrive_voltage_code(3000)
+    );
+    assert_eq!(
+        overdrive_clamp_raw(5600, 3000),
+        overdrive_voltage_code(3000)
+    );
+    assert_eq!(overdrive_clamp_raw(5601, 3000), None);
+}